@@ -0,0 +1,19 @@
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// Average wall time of `runs` calls to `f`
+pub fn time_runs<T>(runs: u32, f: impl Fn() -> T) -> Duration {
+    let start = Instant::now();
+    for _ in 0..runs {
+        black_box(f());
+    }
+    start.elapsed() / runs
+}
+
+/// Print the timings of a baseline and a candidate implementation side by side
+pub fn compare<A, B>(name: &str, runs: u32, baseline: impl Fn() -> A, candidate: impl Fn() -> B) {
+    let base = time_runs(runs, baseline);
+    let cand = time_runs(runs, candidate);
+    let speedup = base.as_secs_f64() / cand.as_secs_f64();
+    println!("{name}: {base:?} -> {cand:?} ({speedup:.1}x)");
+}
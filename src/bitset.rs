@@ -0,0 +1,242 @@
+use std::fmt;
+use std::ops::{BitAnd, BitOr, BitXor, Sub};
+
+/// Fixed size sets of small integers, one bit per member
+macro_rules! bitset {
+    ($(#[$attr:meta])* $name:ident, $iter:ident, $bits:ty) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+        pub struct $name {
+            bits: $bits,
+        }
+
+        $(#[$attr])*
+        impl $name {
+            /// Largest value that can be stored plus one
+            pub const CAPACITY: u32 = <$bits>::BITS;
+
+            pub fn new() -> $name {
+                $name { bits: 0 }
+            }
+
+            pub fn insert(&mut self, val: u32) -> bool {
+                let mask = Self::mask(val);
+                let added = self.bits & mask == 0;
+                self.bits |= mask;
+                added
+            }
+
+            // Days 3 and 6 only ever build sets up
+            #[allow(dead_code)]
+            pub fn remove(&mut self, val: u32) -> bool {
+                let mask = Self::mask(val);
+                let removed = self.bits & mask != 0;
+                self.bits &= !mask;
+                removed
+            }
+
+            pub fn contains(&self, val: u32) -> bool {
+                val < Self::CAPACITY && self.bits & Self::mask(val) != 0
+            }
+
+            /// Number of members
+            pub fn len(&self) -> u32 {
+                self.bits.count_ones()
+            }
+
+            pub fn is_empty(&self) -> bool {
+                self.bits == 0
+            }
+
+            pub fn union(&self, other: &$name) -> $name {
+                $name {
+                    bits: self.bits | other.bits,
+                }
+            }
+
+            pub fn intersection(&self, other: &$name) -> $name {
+                $name {
+                    bits: self.bits & other.bits,
+                }
+            }
+
+            pub fn difference(&self, other: &$name) -> $name {
+                $name {
+                    bits: self.bits & !other.bits,
+                }
+            }
+
+            pub fn symmetric_difference(&self, other: &$name) -> $name {
+                $name {
+                    bits: self.bits ^ other.bits,
+                }
+            }
+
+            #[allow(dead_code)]
+            pub fn is_subset(&self, other: &$name) -> bool {
+                self.bits & !other.bits == 0
+            }
+
+            /// Smallest member
+            pub fn first(&self) -> Option<u32> {
+                if self.is_empty() {
+                    None
+                } else {
+                    Some(self.bits.trailing_zeros())
+                }
+            }
+
+            /// Members in ascending order
+            pub fn iter(&self) -> $iter {
+                $iter { bits: self.bits }
+            }
+
+            fn mask(val: u32) -> $bits {
+                assert!(
+                    val < Self::CAPACITY,
+                    "{val} is too large for {}",
+                    stringify!($name)
+                );
+                1 << val
+            }
+        }
+
+        $(#[$attr])*
+        pub struct $iter {
+            bits: $bits,
+        }
+
+        impl Iterator for $iter {
+            type Item = u32;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                if self.bits == 0 {
+                    return None;
+                }
+                let val = self.bits.trailing_zeros();
+                // Clear the lowest set bit
+                self.bits &= self.bits - 1;
+                Some(val)
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                let remaining = self.bits.count_ones() as usize;
+                (remaining, Some(remaining))
+            }
+        }
+
+        impl IntoIterator for $name {
+            type Item = u32;
+            type IntoIter = $iter;
+
+            fn into_iter(self) -> Self::IntoIter {
+                self.iter()
+            }
+        }
+
+        impl FromIterator<u32> for $name {
+            fn from_iter<I: IntoIterator<Item = u32>>(iter: I) -> Self {
+                let mut results = $name::new();
+                results.extend(iter);
+                results
+            }
+        }
+
+        impl Extend<u32> for $name {
+            fn extend<I: IntoIterator<Item = u32>>(&mut self, iter: I) {
+                for val in iter {
+                    self.insert(val);
+                }
+            }
+        }
+
+        impl BitOr for $name {
+            type Output = $name;
+
+            fn bitor(self, rhs: $name) -> $name {
+                self.union(&rhs)
+            }
+        }
+
+        impl BitAnd for $name {
+            type Output = $name;
+
+            fn bitand(self, rhs: $name) -> $name {
+                self.intersection(&rhs)
+            }
+        }
+
+        impl BitXor for $name {
+            type Output = $name;
+
+            fn bitxor(self, rhs: $name) -> $name {
+                self.symmetric_difference(&rhs)
+            }
+        }
+
+        impl Sub for $name {
+            type Output = $name;
+
+            fn sub(self, rhs: $name) -> $name {
+                self.difference(&rhs)
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.debug_set().entries(self.iter()).finish()
+            }
+        }
+    };
+}
+
+bitset!(
+    // No day needs the narrower set yet, but both widths are part of the API
+    #[allow(dead_code)]
+    BitSet64,
+    BitSet64Iter,
+    u64
+);
+bitset!(BitSet128, BitSet128Iter, u128);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_algebra() {
+        let a: BitSet64 = [1, 3, 5, 63].into_iter().collect();
+        let b: BitSet64 = [3, 4, 5].into_iter().collect();
+        assert_eq!(a.len(), 4);
+        assert!(a.contains(63));
+        assert!(!a.contains(64));
+        assert_eq!((a & b).iter().collect::<Vec<_>>(), vec![3, 5]);
+        assert_eq!((a | b).len(), 5);
+        assert_eq!((a - b).iter().collect::<Vec<_>>(), vec![1, 63]);
+        assert_eq!((a ^ b).iter().collect::<Vec<_>>(), vec![1, 4, 63]);
+        assert!((a & b).is_subset(&a));
+        assert_eq!(b.first(), Some(3));
+        let mut c = a;
+        assert!(c.remove(63));
+        assert!(!c.contains(63));
+    }
+
+    #[test]
+    fn insert_and_remove() {
+        let mut s = BitSet128::new();
+        assert!(s.insert(127));
+        assert!(!s.insert(127));
+        assert!(s.insert(0));
+        assert_eq!(format!("{s:?}"), "{0, 127}");
+        assert!(s.remove(0));
+        assert!(!s.remove(0));
+        assert_eq!(s.len(), 1);
+        assert!(s.is_subset(&[5, 127].into_iter().collect()));
+    }
+
+    #[test]
+    #[should_panic]
+    fn out_of_range() {
+        BitSet64::new().insert(64);
+    }
+}
@@ -1,49 +1,510 @@
-use std::cmp;
-use std::collections::BinaryHeap;
-use std::fs;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::time::{Duration, Instant};
 
-pub fn part_a() {
-    let file_path = "src/input1.txt";
+use crate::top_k::TopK;
+
+const DAY: i32 = 1;
+
+fn file_path() -> String {
+    format!("src/input{DAY}.txt")
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Elf {
+    /// 1 based position in the input
+    pub id: usize,
+    pub items: Vec<u64>,
+    pub total: u64,
+}
+
+#[derive(Debug)]
+pub enum InventoryError {
+    Io(io::Error),
+    /// 1 based line number and its text
+    BadItem {
+        line: usize,
+        text: String,
+    },
+    /// An elf's calories do not fit in a u64
+    Overflow {
+        elf: usize,
+    },
+}
+
+impl fmt::Display for InventoryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InventoryError::Io(e) => write!(f, "could not read inventory: {e}"),
+            InventoryError::BadItem { line, text } => {
+                write!(f, "line {line}: {text:?} is not a calorie count")
+            }
+            InventoryError::Overflow { elf } => write!(f, "elf {elf} carries too many calories"),
+        }
+    }
+}
+
+impl From<io::Error> for InventoryError {
+    fn from(e: io::Error) -> Self {
+        InventoryError::Io(e)
+    }
+}
+
+/// Every elf's snacks, in input order
+#[derive(Debug, Clone, Default)]
+pub struct ElfInventory {
+    pub elves: Vec<Elf>,
+}
+
+impl ElfInventory {
+    /// Read the inventory in one pass. Elves are separated by one or more blank lines.
+    pub fn from_reader<R: BufRead>(reader: R) -> Result<ElfInventory, InventoryError> {
+        let mut elves = Vec::new();
+        let mut current: Option<Elf> = None;
 
-    let contents = fs::read_to_string(file_path).expect("Should have been able to read the file");
+        for (i, line) in reader.lines().enumerate() {
+            let line = line?;
+            let text = line.trim();
+            if text.is_empty() {
+                elves.extend(current.take());
+                continue;
+            }
 
-    let mut current = 0;
-    let mut best = 0;
-    for line in contents.lines() {
-        if line.is_empty() {
-            best = cmp::max(best, current);
-            current = 0;
-        } else {
-            current += line.parse::<i32>().unwrap();
+            let calories: u64 = text.parse().map_err(|_| InventoryError::BadItem {
+                line: i + 1,
+                text: text.to_string(),
+            })?;
+            let elf = current.get_or_insert_with(|| Elf {
+                id: elves.len() + 1,
+                items: Vec::new(),
+                total: 0,
+            });
+            elf.items.push(calories);
+            elf.total = elf
+                .total
+                .checked_add(calories)
+                .ok_or(InventoryError::Overflow { elf: elf.id })?;
         }
+        // Last elf has no trailing blank line
+        elves.extend(current);
+
+        Ok(ElfInventory { elves })
     }
+
+    pub fn from_file(path: &str) -> Result<ElfInventory, InventoryError> {
+        ElfInventory::from_reader(BufReader::new(File::open(path)?))
+    }
+
+    /// The `count` elves carrying the most, most first. Ties go to the lower id.
+    pub fn top(&self, count: usize) -> Vec<&Elf> {
+        let mut top = TopK::by_key(count, |e: &&Elf| e.total);
+        top.extend(&self.elves);
+        top.into_sorted_vec()
+    }
+}
+
+fn load() -> ElfInventory {
+    ElfInventory::from_file(&file_path()).unwrap_or_else(|e| panic!("{e}"))
+}
+
+fn elf_ids(elves: &[&Elf]) -> String {
+    let ids: Vec<String> = elves.iter().map(|e| e.id.to_string()).collect();
+    ids.join(", ")
+}
+
+/// Print the combined total of the top `count` elves and who they are
+fn report_top(inventory: &ElfInventory, count: usize, label: &str) {
+    let top = inventory.top(count);
+    let results: u64 = top.iter().map(|e| e.total).sum();
+
+    println!("Day {DAY}{label} best:\n{results}");
+    println!("Carried by elves {}", elf_ids(&top));
+}
+
+pub fn part_a() {
+    let inventory = load();
+
+    let best = inventory.top(1);
+    let results = best.first().map_or(0, |e| e.total);
     // 70764
-    println!("Day 1a best:\n{best}");
+    println!("Day {DAY}a best:\n{results}");
+    println!("Carried by elf {}", elf_ids(&best));
 }
 
 pub fn part_b() {
-    let file_path = "src/input1.txt";
+    // 203905
+    report_top(&load(), 3, "b");
+}
 
-    let contents = fs::read_to_string(file_path).expect("Should have been able to read the file");
+/// Args: `[k]`, the number of elves to total (default 3)
+pub fn part_top(args: &[String]) {
+    let count: usize = args
+        .first()
+        .map_or(3, |k| k.parse().expect("k should be a number"));
 
-    let count = 3;
-    let mut current = 0;
-    let mut heap = BinaryHeap::new();
-    for line in contents.lines() {
-        heap.push(-current);
-        if line.is_empty() {
-            if heap.len() > count {
-                heap.pop();
+    report_top(&load(), count, &format!(" top {count}"));
+}
+
+/// Linearly interpolated percentile of already sorted values
+fn percentile(sorted: &[u64], p: f64) -> f64 {
+    match sorted.len() {
+        0 => 0.0,
+        1 => sorted[0] as f64,
+        n => {
+            let rank = p / 100.0 * (n - 1) as f64;
+            let lo = rank.floor() as usize;
+            let hi = rank.ceil() as usize;
+            let weight = rank - lo as f64;
+            sorted[lo] as f64 * (1.0 - weight) + sorted[hi] as f64 * weight
+        }
+    }
+}
+
+const PERCENTILES: [u8; 7] = [5, 10, 25, 50, 75, 90, 95];
+const HISTOGRAM_BINS: usize = 10;
+const HISTOGRAM_WIDTH: usize = 50;
+
+/// Summary of the calories carried across all elves
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalorieStats {
+    pub elves: usize,
+    pub min_items: usize,
+    pub max_items: usize,
+    pub mean_items: f64,
+    pub min: u64,
+    pub max: u64,
+    pub mean: f64,
+    pub median: f64,
+    pub std_dev: f64,
+    pub percentiles: Vec<(u8, f64)>,
+    /// Inclusive lower and upper bound of each bin and the number of elves in it
+    pub histogram: Vec<(u64, u64, usize)>,
+}
+
+impl CalorieStats {
+    pub fn new(inventory: &ElfInventory) -> CalorieStats {
+        let mut totals: Vec<u64> = inventory.elves.iter().map(|e| e.total).collect();
+        totals.sort_unstable();
+        let item_counts: Vec<usize> = inventory.elves.iter().map(|e| e.items.len()).collect();
+
+        let elves = totals.len();
+        let n = elves.max(1) as f64;
+        let mean = totals.iter().map(|&t| t as f64).sum::<f64>() / n;
+        let variance = totals
+            .iter()
+            .map(|&t| (t as f64 - mean).powi(2))
+            .sum::<f64>()
+            / n;
+        let min = totals.first().copied().unwrap_or(0);
+        let max = totals.last().copied().unwrap_or(0);
+
+        CalorieStats {
+            elves,
+            min_items: item_counts.iter().copied().min().unwrap_or(0),
+            max_items: item_counts.iter().copied().max().unwrap_or(0),
+            mean_items: item_counts.iter().sum::<usize>() as f64 / n,
+            min,
+            max,
+            mean,
+            median: percentile(&totals, 50.0),
+            std_dev: variance.sqrt(),
+            percentiles: PERCENTILES
+                .iter()
+                .map(|&p| (p, percentile(&totals, p as f64)))
+                .collect(),
+            histogram: CalorieStats::histogram(&totals, min, max),
+        }
+    }
+
+    fn histogram(totals: &[u64], min: u64, max: u64) -> Vec<(u64, u64, usize)> {
+        if totals.is_empty() {
+            return Vec::new();
+        }
+        let bin_width = ((max - min) / HISTOGRAM_BINS as u64).max(1) + 1;
+        let mut bins: Vec<(u64, u64, usize)> = (0..HISTOGRAM_BINS as u64)
+            .map(|i| min + i * bin_width)
+            .take_while(|&lo| lo <= max)
+            .map(|lo| (lo, lo + bin_width - 1, 0))
+            .collect();
+        for &t in totals {
+            bins[((t - min) / bin_width) as usize].2 += 1;
+        }
+        bins
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        out += &format!("Elves: {}\n", self.elves);
+        out += &format!(
+            "Items per elf: min {} / mean {:.2} / max {}\n",
+            self.min_items, self.mean_items, self.max_items
+        );
+        out += &format!("Calories min: {}\n", self.min);
+        out += &format!("Calories max: {}\n", self.max);
+        out += &format!("Calories mean: {:.2}\n", self.mean);
+        out += &format!("Calories median: {:.1}\n", self.median);
+        out += &format!("Calories std dev: {:.2}\n", self.std_dev);
+        for (p, v) in &self.percentiles {
+            out += &format!("Calories p{p}: {v:.1}\n");
+        }
+
+        out += "Histogram:\n";
+        let tallest = self.histogram.iter().map(|b| b.2).max().unwrap_or(0).max(1);
+        let label_width = self.max.to_string().len();
+        for (lo, hi, count) in &self.histogram {
+            let bar = "#".repeat((count * HISTOGRAM_WIDTH).div_ceil(tallest));
+            out += &format!("{lo:>label_width$}-{hi:>label_width$} | {bar} {count}\n");
+        }
+        out
+    }
+
+    pub fn to_json(&self) -> String {
+        let percentiles: Vec<String> = self
+            .percentiles
+            .iter()
+            .map(|(p, v)| format!("\"p{p}\": {v}"))
+            .collect();
+        let histogram: Vec<String> = self
+            .histogram
+            .iter()
+            .map(|(lo, hi, count)| format!("{{\"min\": {lo}, \"max\": {hi}, \"count\": {count}}}"))
+            .collect();
+        format!(
+            "{{\"elves\": {}, \"items_per_elf\": {{\"min\": {}, \"mean\": {}, \"max\": {}}}, \
+             \"min\": {}, \"max\": {}, \"mean\": {}, \"median\": {}, \"std_dev\": {}, \
+             \"percentiles\": {{{}}}, \"histogram\": [{}]}}",
+            self.elves,
+            self.min_items,
+            self.mean_items,
+            self.max_items,
+            self.min,
+            self.max,
+            self.mean,
+            self.median,
+            self.std_dev,
+            percentiles.join(", "),
+            histogram.join(", ")
+        )
+    }
+}
+
+/// Args: `[text|json]`, the report format (default text)
+pub fn part_stats(args: &[String]) {
+    let stats = CalorieStats::new(&load());
+
+    match args.first().map_or("text", |f| f.as_str()) {
+        "text" => print!("{}", stats.to_text()),
+        "json" => println!("{}", stats.to_json()),
+        other => panic!("unknown stats format {other}, expected text or json"),
+    }
+}
+
+/// One snack handed from one elf to another, by elf id
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnackMove {
+    pub calories: u64,
+    pub from: usize,
+    pub to: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct RebalancePlan {
+    /// Net moves from each snack's original elf to its final elf
+    pub moves: Vec<SnackMove>,
+    /// Final total per elf, in elf id order
+    pub totals: Vec<u64>,
+    pub spread_before: u64,
+    /// Max minus min of the final totals
+    pub spread: u64,
+}
+
+/// Snacks handed between elves while searching for an even split
+struct Balancer {
+    calories: Vec<u64>,
+    // Elf index each snack started with
+    origin: Vec<usize>,
+    // Snack indexes each elf currently holds
+    packs: Vec<Vec<usize>>,
+    totals: Vec<u64>,
+}
+
+impl Balancer {
+    fn new(inventory: &ElfInventory) -> Balancer {
+        let mut calories = Vec::new();
+        let mut origin = Vec::new();
+        let mut packs = Vec::new();
+        for (i, elf) in inventory.elves.iter().enumerate() {
+            let mut pack = Vec::new();
+            for &c in &elf.items {
+                pack.push(calories.len());
+                calories.push(c);
+                origin.push(i);
             }
-            current = 0;
-        } else {
-            current += line.parse::<i32>().unwrap();
+            packs.push(pack);
+        }
+        let totals = inventory.elves.iter().map(|e| e.total).collect();
+        Balancer {
+            calories,
+            origin,
+            packs,
+            totals,
+        }
+    }
+
+    /// Index of the heaviest and lightest elves
+    fn extremes(&self) -> (usize, usize) {
+        let heavy = (0..self.totals.len()).max_by_key(|&i| self.totals[i]);
+        let light = (0..self.totals.len()).min_by_key(|&i| self.totals[i]);
+        (heavy.unwrap_or(0), light.unwrap_or(0))
+    }
+
+    fn spread(&self) -> u64 {
+        let (heavy, light) = self.extremes();
+        self.totals.get(heavy).map_or(0, |h| h - self.totals[light])
+    }
+
+    fn hand_over(&mut self, from: usize, pos: usize, to: usize) {
+        let snack = self.packs[from].swap_remove(pos);
+        self.totals[from] -= self.calories[snack];
+        self.totals[to] += self.calories[snack];
+        self.packs[to].push(snack);
+    }
+
+    /// Move or swap snacks between a heavier and a lighter elf if it brings their totals closer.
+    ///
+    /// The heavier elf never ends up heavier than before, and the lighter one never lighter,
+    /// so the overall spread can't grow.
+    fn even_out(&mut self, heavy: usize, light: usize, allow_swap: bool) -> bool {
+        if self.totals[heavy] <= self.totals[light] {
+            return false;
+        }
+        let diff = self.totals[heavy] - self.totals[light];
+        // Handing over `delta` calories leaves the pair `|diff - 2 * delta|` apart
+        let gap = |delta: u64| diff.abs_diff(2 * delta);
+
+        let best_move = self.packs[heavy]
+            .iter()
+            .enumerate()
+            .map(|(pos, &s)| (pos, self.calories[s]))
+            .filter(|&(_, c)| c > 0 && c < diff)
+            .min_by_key(|&(_, c)| gap(c));
+
+        let mut best_swap = None;
+        if allow_swap {
+            for (hp, &hs) in self.packs[heavy].iter().enumerate() {
+                for (lp, &ls) in self.packs[light].iter().enumerate() {
+                    let (hc, lc) = (self.calories[hs], self.calories[ls]);
+                    if hc <= lc || hc - lc >= diff {
+                        continue;
+                    }
+                    if best_swap.is_none_or(|(_, _, d)| gap(hc - lc) < gap(d)) {
+                        best_swap = Some((hp, lp, hc - lc));
+                    }
+                }
+            }
+        }
+
+        match (best_move, best_swap) {
+            (Some((_, c)), Some((hp, lp, d))) if gap(d) < gap(c) => {
+                self.swap(heavy, hp, light, lp);
+                true
+            }
+            (Some((pos, _)), _) => {
+                self.hand_over(heavy, pos, light);
+                true
+            }
+            (None, Some((hp, lp, _))) => {
+                self.swap(heavy, hp, light, lp);
+                true
+            }
+            (None, None) => false,
+        }
+    }
+
+    fn swap(&mut self, heavy: usize, hp: usize, light: usize, lp: usize) {
+        // Hand over the lighter elf's snack first so `hp` stays valid
+        self.hand_over(light, lp, heavy);
+        self.hand_over(heavy, hp, light);
+    }
+
+    fn plan(&self, inventory: &ElfInventory, spread_before: u64) -> RebalancePlan {
+        let mut moves: Vec<SnackMove> = Vec::new();
+        for (elf, pack) in self.packs.iter().enumerate() {
+            for &s in pack {
+                if self.origin[s] != elf {
+                    moves.push(SnackMove {
+                        calories: self.calories[s],
+                        from: inventory.elves[self.origin[s]].id,
+                        to: inventory.elves[elf].id,
+                    });
+                }
+            }
+        }
+        moves.sort_by_key(|m| (m.from, m.to, m.calories));
+
+        RebalancePlan {
+            moves,
+            totals: self.totals.clone(),
+            spread_before,
+            spread: self.spread(),
         }
     }
-    let results: i32 = heap.iter().sum();
-    let actual_results = -results;
-    // 203905
-    println!("Day 1b best:\n{actual_results}");
+}
+
+/// Hand snacks between elves so their totals are as even as possible.
+///
+/// Greedily moves single snacks from the heaviest to the lightest elf, then
+/// searches moves and swaps between the extremes and every other elf until
+/// nothing helps or `budget` runs out.
+pub fn rebalance(inventory: &ElfInventory, budget: Duration) -> RebalancePlan {
+    let start = Instant::now();
+    let mut balancer = Balancer::new(inventory);
+    let spread_before = balancer.spread();
+
+    // Greedy
+    while start.elapsed() < budget {
+        let (heavy, light) = balancer.extremes();
+        if !balancer.even_out(heavy, light, false) {
+            break;
+        }
+    }
+
+    // Local search
+    while start.elapsed() < budget {
+        let (heavy, light) = balancer.extremes();
+        let improved = balancer.even_out(heavy, light, true)
+            || (0..balancer.totals.len()).any(|other| {
+                balancer.even_out(heavy, other, true) || balancer.even_out(other, light, true)
+            });
+        if !improved {
+            break;
+        }
+    }
+
+    balancer.plan(inventory, spread_before)
+}
+
+/// Args: `[budget_ms]`, how long to search (default 5000)
+pub fn part_rebalance(args: &[String]) {
+    let budget_ms: u64 = args.first().map_or(5000, |ms| {
+        ms.parse().expect("budget should be milliseconds")
+    });
+
+    let inventory = load();
+    let plan = rebalance(&inventory, Duration::from_millis(budget_ms));
+
+    for m in &plan.moves {
+        println!(
+            "Move a {} calorie snack from elf {} to elf {}",
+            m.calories, m.from, m.to
+        );
+    }
+    println!("Day {DAY} rebalance moves:\n{}", plan.moves.len());
+    println!("Spread {} -> {}", plan.spread_before, plan.spread);
+    let lightest = plan.totals.iter().min().unwrap_or(&0);
+    let heaviest = plan.totals.iter().max().unwrap_or(&0);
+    println!("Elves now carry {lightest} to {heaviest} calories");
 }
 
 #[cfg(test)]
@@ -59,4 +520,60 @@ mod tests {
     fn do_part_b() {
         part_b();
     }
+
+    #[test]
+    fn streams_inventory() {
+        let input = "1000\n2000\n3000\n\n4000\n\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000";
+        let inventory = ElfInventory::from_reader(input.as_bytes()).unwrap();
+
+        assert_eq!(inventory.elves.len(), 5);
+        assert_eq!(inventory.elves[2].items, vec![5000, 6000]);
+        let top: Vec<(usize, u64)> = inventory.top(3).iter().map(|e| (e.id, e.total)).collect();
+        assert_eq!(top, vec![(4, 24000), (3, 11000), (5, 10000)]);
+    }
+
+    #[test]
+    fn rejects_bad_inventory() {
+        let err = ElfInventory::from_reader("1\n\nsnack\n".as_bytes()).unwrap_err();
+        assert!(matches!(err, InventoryError::BadItem { line: 3, .. }));
+
+        let input = format!("{}\n1\n", u64::MAX);
+        let err = ElfInventory::from_reader(input.as_bytes()).unwrap_err();
+        assert!(matches!(err, InventoryError::Overflow { elf: 1 }));
+    }
+
+    #[test]
+    fn calorie_stats() {
+        let input = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000";
+        let inventory = ElfInventory::from_reader(input.as_bytes()).unwrap();
+        let stats = CalorieStats::new(&inventory);
+
+        assert_eq!(stats.elves, 5);
+        assert_eq!((stats.min_items, stats.max_items), (1, 3));
+        assert_eq!((stats.min, stats.max), (4000, 24000));
+        assert_eq!(stats.mean, 11000.0);
+        assert_eq!(stats.median, 10000.0);
+        assert_eq!(stats.percentiles[2], (25, 6000.0));
+        assert_eq!(stats.histogram.iter().map(|b| b.2).sum::<usize>(), 5);
+        assert!(stats.to_json().starts_with("{\"elves\": 5, "));
+        assert!(stats.to_text().contains("Calories median: 10000.0"));
+    }
+
+    #[test]
+    fn rebalances_snacks() {
+        let input = "6\n4\n2\n\n1\n\n3";
+        let inventory = ElfInventory::from_reader(input.as_bytes()).unwrap();
+        let plan = rebalance(&inventory, Duration::from_secs(1));
+
+        assert_eq!(plan.spread_before, 11);
+        assert_eq!(plan.spread, 1);
+        assert_eq!(plan.totals.iter().sum::<u64>(), 16);
+        // Replaying the moves gives the final totals
+        let mut totals: Vec<u64> = inventory.elves.iter().map(|e| e.total).collect();
+        for m in &plan.moves {
+            totals[m.from - 1] -= m.calories;
+            totals[m.to - 1] += m.calories;
+        }
+        assert_eq!(totals, plan.totals);
+    }
 }
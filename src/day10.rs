@@ -1,6 +1,8 @@
 use std::fs;
 use std::str;
 
+use crate::ocr;
+
 const DAY: i32 = 10;
 
 fn file_path() -> String {
@@ -148,34 +150,51 @@ pub fn part_a() {
     println!("Day {DAY}a best:\n{results}");
 }
 
-pub fn part_b() {
-    let contents =
-        fs::read_to_string(file_path()).expect("Should have been able to read {DAY} the file");
-
+/// Rows of `#` and `.` drawn by the CRT
+fn render(contents: &str) -> Vec<String> {
     let it = Input {
         lines: contents.lines(),
     };
 
     let machine = Machine::new(it);
 
+    let mut rows = Vec::new();
+    let mut row = String::new();
     for (cycle, x) in machine {
-        let current_column = cycle % 40;
+        // Pixel being drawn this cycle, 0 based
+        let current_column = (cycle - 1) % 40;
 
-        if x <= current_column && x + 2 >= current_column {
-            print!("#");
+        if (x - current_column).abs() <= 1 {
+            row.push('#');
         } else {
-            print!(".");
+            row.push('.');
         }
 
         if cycle % 40 == 0 {
-            println!();
+            rows.push(row);
+            row = String::new();
         }
         // println!("{cycle}, {x}");
     }
+    rows
+}
+
+/// Letters displayed on the CRT
+fn crt_message(contents: &str) -> Result<String, ocr::OcrError> {
+    ocr::read(&render(contents))
+}
+
+pub fn part_b() {
+    let contents =
+        fs::read_to_string(file_path()).expect("Should have been able to read {DAY} the file");
+
+    for row in render(&contents) {
+        println!("{row}");
+    }
 
-    let results = "read picture";
+    let results = crt_message(&contents).unwrap_or_else(|e| panic!("{e}"));
     // Solution RFZEKBFA
-    println!("Day {DAY}a best:\n{results}");
+    println!("Day {DAY}b best:\n{results}");
 }
 
 #[cfg(test)]
@@ -191,4 +210,10 @@ mod tests {
     fn do_part_b() {
         part_b();
     }
+
+    #[test]
+    fn reads_crt_message() {
+        let contents = fs::read_to_string(file_path()).unwrap();
+        assert_eq!(crt_message(&contents), Ok(String::from("RFZEKBFA")));
+    }
 }
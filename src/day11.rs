@@ -2,6 +2,8 @@ use std::collections::HashMap;
 use std::fs;
 use std::str;
 
+use crate::top_k::top_k;
+
 const DAY: i64 = 11;
 
 fn file_path() -> String {
@@ -246,19 +248,16 @@ impl<'a> Iterator for Input2<'a> {
     }
 }
 
-pub fn part_a() {
-    let contents =
-        fs::read_to_string(file_path()).expect("Should have been able to read {DAY} the file");
-
-    let it = Input {
-        lines: contents.lines(),
-    };
-
-    let mut monkeys: Vec<Monkey> = it.collect();
-
+/// How many items each monkey inspects over `rounds`
+fn interaction_counts(
+    monkeys: &mut [Monkey],
+    rounds: usize,
+    divisor: i64,
+    modulus: i64,
+) -> HashMap<usize, usize> {
     let mut monkey_iteraction_counts: HashMap<usize, usize> = HashMap::new();
 
-    for _ in 0..20 {
+    for _ in 0..rounds {
         for i in 0..monkeys.len() {
             let m = monkeys.get_mut(i).unwrap();
 
@@ -270,7 +269,7 @@ pub fn part_a() {
                 .or_insert(m.items.len());
 
             // Find where this monkey is throwing all its items
-            let mut monkey_updates = m.run(3, 0);
+            let mut monkey_updates = m.run(divisor, modulus);
 
             // Give the monkeys the new items
             for (monkey_id, new_items) in monkey_updates.iter_mut() {
@@ -279,14 +278,32 @@ pub fn part_a() {
             }
         }
     }
+    monkey_iteraction_counts
+}
 
-    for i in 0..monkeys.len() {
-        let interaction_count = monkey_iteraction_counts.get(&i).unwrap();
-        // println!("Monkey {i} inspected items {interaction_count} times.");
-    }
-    let mut counts: Vec<&usize> = monkey_iteraction_counts.values().collect();
-    counts.sort();
-    let results: usize = counts.iter().rev().take(2).map(|e| **e).product();
+/// Product of the `count` highest interaction counts
+fn monkey_business(counts: &HashMap<usize, usize>, count: usize) -> usize {
+    top_k(counts.values().copied(), count).iter().product()
+}
+
+/// Worry levels are kept in bounds with a multiple of all test divisors
+fn worry_modulus(monkeys: &[Monkey]) -> i64 {
+    // All the numbers in my example are primes so no point being too clever
+    monkeys.iter().map(|m| m.test.operation.divisor).product()
+}
+
+pub fn part_a() {
+    let contents =
+        fs::read_to_string(file_path()).expect("Should have been able to read {DAY} the file");
+
+    let it = Input {
+        lines: contents.lines(),
+    };
+
+    let mut monkeys: Vec<Monkey> = it.collect();
+
+    let counts = interaction_counts(&mut monkeys, 20, 3, 0);
+    let results = monkey_business(&counts, 2);
 
     // Solution 50616
     println!("Day {DAY}a best:\n{results}");
@@ -302,50 +319,42 @@ pub fn part_b() {
 
     let mut monkeys: Vec<Monkey> = it.collect();
 
-    let mut monkey_iteraction_counts: HashMap<usize, usize> = HashMap::new();
+    let modulus = worry_modulus(&monkeys);
+    let counts = interaction_counts(&mut monkeys, 10000, 0, modulus);
+    let results = monkey_business(&counts, 2);
 
-    // All the numbers in my example are primes so no point being too clever
-    let mut modulus = 1;
-    for i in 0..monkeys.len() {
-        let m = monkeys.get_mut(i).unwrap();
+    // Solution 11309046332
+    println!("Day {DAY}b best:\n{results}");
+}
 
-        modulus *= m.test.operation.divisor;
-    }
+/// Args: `[k] [a|b]`, the number of monkeys to multiply (default 2) and which
+/// part's worry rules to use (default b)
+pub fn part_top(args: &[String]) {
+    let count: usize = args
+        .first()
+        .map_or(2, |k| k.parse().expect("k should be a number"));
+    let part = args.get(1).map_or("b", |p| p.as_str());
 
-    for _ in 0..10000 {
-        for i in 0..monkeys.len() {
-            let m = monkeys.get_mut(i).unwrap();
+    let contents =
+        fs::read_to_string(file_path()).expect("Should have been able to read {DAY} the file");
 
-            // println!("Monkey {i}:");
-            // Keep track of how many items this monkey is about to interact with
-            monkey_iteraction_counts
-                .entry(i)
-                .and_modify(|c| *c += m.items.len())
-                .or_insert(m.items.len());
+    let it = Input {
+        lines: contents.lines(),
+    };
 
-            // Find where this monkey is throwing all its items
-            // a multiple of all tests
-            // Use a multiple of all test divisions to keep the worry in bounds
-            let mut monkey_updates = m.run(0, modulus);
+    let mut monkeys: Vec<Monkey> = it.collect();
 
-            // Give the monkeys the new items
-            for (monkey_id, new_items) in monkey_updates.iter_mut() {
-                let m = monkeys.get_mut(*monkey_id).unwrap();
-                m.items.append(new_items);
-            }
+    let counts = match part {
+        "a" => interaction_counts(&mut monkeys, 20, 3, 0),
+        "b" => {
+            let modulus = worry_modulus(&monkeys);
+            interaction_counts(&mut monkeys, 10000, 0, modulus)
         }
-    }
-
-    for i in 0..monkeys.len() {
-        let interaction_count = monkey_iteraction_counts.get(&i).unwrap();
-        // println!("Monkey {i} inspected items {interaction_count} times.");
-    }
-    let mut counts: Vec<&usize> = monkey_iteraction_counts.values().collect();
-    counts.sort();
-    let results: usize = counts.iter().rev().take(2).map(|e| **e).product();
+        _ => panic!("part should be a or b"),
+    };
+    let results = monkey_business(&counts, count);
 
-    // Solution 11309046332
-    println!("Day {DAY}b best:\n{results}");
+    println!("Day {DAY}{part} top {count}:\n{results}");
 }
 
 #[cfg(test)]
@@ -1,68 +1,115 @@
+mod explore;
+mod game;
+mod model;
+mod tournament;
+
+use std::fmt;
 use std::fs;
 use std::str;
 
+use game::{Game, Shape};
+
 const DAY: i32 = 2;
 
+#[derive(Debug, PartialEq, Eq)]
+enum ParseError {
+    Shape(String),
+    Outcome(String),
+    /// 1 based line number of a line without two columns
+    Line(usize),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Shape(s) => write!(f, "{s:?} is not a shape"),
+            ParseError::Outcome(s) => write!(f, "{s:?} is not an outcome"),
+            ParseError::Line(n) => write!(f, "line {n} should have two columns"),
+        }
+    }
+}
+
 struct Input<'a> {
     lines: str::Lines<'a>,
+    // 1 based number of the last line read
+    line: usize,
 }
 impl<'a> Iterator for Input<'a> {
-    type Item = (&'a str, &'a str);
+    type Item = Result<(&'a str, &'a str), ParseError>;
 
     fn next(&mut self) -> Option<Self::Item> {
         let line = self.lines.next();
+        self.line += 1;
         match line {
             Some(text) => {
                 let mut split = text.split_whitespace();
-                let l = split.next().unwrap();
-                let r = split.next().unwrap();
-                Some((l, r))
+                match (split.next(), split.next()) {
+                    (Some(l), Some(r)) => Some(Ok((l, r))),
+                    _ => Some(Err(ParseError::Line(self.line))),
+                }
             }
             None => None,
         }
     }
 }
 
+/// Opponent shape and right column symbol of each round
+fn parse_guide<'a>(game: &Game, contents: &'a str) -> Result<Vec<(Shape, &'a str)>, ParseError> {
+    let it = Input {
+        lines: contents.lines(),
+        line: 0,
+    };
+
+    it.map(|row| {
+        let (l, r) = row?;
+        let opponent = game.parse_opponent(l)?;
+        Ok((opponent, r))
+    })
+    .collect()
+}
+
+/// Score the guide, choosing the player's shape from each line's columns
+fn guide_score(
+    game: &Game,
+    contents: &str,
+    choose: impl Fn(Shape, &str) -> Result<Shape, ParseError>,
+) -> Result<u32, ParseError> {
+    let mut total = 0;
+    for (opponent, r) in parse_guide(game, contents)? {
+        let player = choose(opponent, r)?;
+        total += game.round_score(player, opponent);
+    }
+    Ok(total)
+}
+
+/// Right column is the shape to play
+fn score_a(game: &Game, contents: &str) -> Result<u32, ParseError> {
+    guide_score(game, contents, |_, r| game.parse_player(r))
+}
+
+/// Right column of every round as the shape to play
+fn guide_shapes(game: &Game, contents: &str) -> Result<Vec<Shape>, ParseError> {
+    parse_guide(game, contents)?
+        .iter()
+        .map(|(_, r)| game.parse_player(r))
+        .collect()
+}
+
+/// Right column is the outcome to aim for
+fn score_b(game: &Game, contents: &str) -> Result<u32, ParseError> {
+    guide_score(game, contents, |opponent, r| {
+        let outcome = game.parse_outcome(r)?;
+        Ok(game.for_outcome(opponent, outcome))
+    })
+}
+
 pub fn part_a() {
     let file_path = format!("src/input{DAY}.txt");
 
     let contents =
         fs::read_to_string(file_path).expect("Should have been able to read {DAY} the file");
 
-    let it = Input {
-        lines: contents.lines(),
-    };
-    let mut total = 0;
-    for (l, r) in it {
-        let outcome = match l {
-            "A" => match r {
-                "X" => 3,
-                "Y" => 6,
-                "Z" => 0,
-                _ => panic!("unmatched"),
-            },
-            "B" => match r {
-                "X" => 0,
-                "Y" => 3,
-                "Z" => 6,
-                _ => panic!("unmatched"),
-            },
-            "C" => match r {
-                "X" => 6,
-                "Y" => 0,
-                "Z" => 3,
-                _ => panic!("unmatched"),
-            },
-            _ => panic!("unmatched"),
-        };
-        let shape = match r {
-            "X" => 1,
-            "Y" => 2,
-            "Z" => 3,
-            _ => panic!("unmatched"),
-        };
-        total += shape + outcome;
-    }
+    let total = score_a(&Game::classic(), &contents).unwrap_or_else(|e| panic!("{e}"));
 
     // // Solution 13052
     println!("Day {DAY}a best:\n{total}");
@@ -74,48 +121,186 @@ pub fn part_b() {
     let contents =
         fs::read_to_string(file_path).expect("Should have been able to read {DAY} the file");
 
-    let it = Input {
-        lines: contents.lines(),
-    };
-    let mut total = 0;
-    for (l, r) in it {
-        // X means you need to lose = 0
-        // Y means you need to end the round in a draw = 3
-        // Z means you need to win = 6
-        let outcome = match r {
-            "X" => 0,
-            "Y" => 3,
-            "Z" => 6,
-            _ => panic!("unmatched"),
-        };
-        let shape = match l {
-            "A" => match r {
-                "X" => 3,
-                "Y" => 1,
-                "Z" => 2,
-                _ => panic!("unmatched"),
-            },
-            "B" => match r {
-                "X" => 1,
-                "Y" => 2,
-                "Z" => 3,
-                _ => panic!("unmatched"),
-            },
-            "C" => match r {
-                "X" => 2,
-                "Y" => 3,
-                "Z" => 1,
-                _ => panic!("unmatched"),
-            },
-            _ => panic!("unmatched"),
-        };
-        total += shape + outcome;
-    }
+    let total = score_b(&Game::classic(), &contents).unwrap_or_else(|e| panic!("{e}"));
 
     // // Solution 13693
     println!("Day {DAY}b best:\n{total}");
 }
 
+/// Args: `[game] [guide]`, a preset (`rps`, `rpsls`) or definition file (default rps),
+/// and the strategy guide to score (default the puzzle input)
+pub fn part_game(args: &[String]) {
+    let game =
+        Game::load(args.first().map_or("rps", |g| g.as_str())).unwrap_or_else(|e| panic!("{e}"));
+    let file_path = args
+        .get(1)
+        .cloned()
+        .unwrap_or_else(|| format!("src/input{DAY}.txt"));
+
+    let contents =
+        fs::read_to_string(file_path).expect("Should have been able to read {DAY} the file");
+
+    let total_a = score_a(&game, &contents).unwrap_or_else(|e| panic!("{e}"));
+    let total_b = score_b(&game, &contents).unwrap_or_else(|e| panic!("{e}"));
+
+    println!("Day {DAY} {}:", game.name);
+    println!("Shapes: {total_a}");
+    println!("Outcomes: {total_b}");
+}
+
+/// Args: `[game] [guide]`, as for `part_game`
+pub fn part_explore(args: &[String]) {
+    let game =
+        Game::load(args.first().map_or("rps", |g| g.as_str())).unwrap_or_else(|e| panic!("{e}"));
+    let file_path = args
+        .get(1)
+        .cloned()
+        .unwrap_or_else(|| format!("src/input{DAY}.txt"));
+
+    let contents =
+        fs::read_to_string(file_path).expect("Should have been able to read {DAY} the file");
+
+    let rows = parse_guide(&game, &contents).unwrap_or_else(|e| panic!("{e}"));
+    let (symbols, results) = explore::explore(&game, &rows);
+
+    println!("Day {DAY} interpretations of {}:", symbols.join("/"));
+    println!("rank  score  reading");
+    for (i, r) in results.iter().enumerate() {
+        let kind = match r.meaning {
+            explore::Meaning::Shapes(_) => "shapes",
+            explore::Meaning::Outcomes(_) => "outcomes",
+        };
+        let marker = if r.intended { " *" } else { "" };
+        println!(
+            "{:>4}  {:>5}  {kind:<8} {}{marker}",
+            i + 1,
+            r.score,
+            r.describe(&game, &symbols)
+        );
+    }
+    if let (Some(best), Some(worst)) = (results.first(), results.last()) {
+        println!(
+            "Range {} to {}, * marks the game's own symbols",
+            worst.score, best.score
+        );
+    }
+}
+
+/// Args: `[order] [game] [guide]`, the highest Markov order to try (default 2),
+/// then as for `part_game`
+pub fn part_model(args: &[String]) {
+    let order: usize = args
+        .first()
+        .map_or(2, |k| k.parse().expect("order should be a number"));
+    let game =
+        Game::load(args.get(1).map_or("rps", |g| g.as_str())).unwrap_or_else(|e| panic!("{e}"));
+    let file_path = args
+        .get(2)
+        .cloned()
+        .unwrap_or_else(|| format!("src/input{DAY}.txt"));
+
+    let contents =
+        fs::read_to_string(file_path).expect("Should have been able to read {DAY} the file");
+
+    let rows = parse_guide(&game, &contents).unwrap_or_else(|e| panic!("{e}"));
+    let opponent: Vec<Shape> = rows.iter().map(|(o, _)| *o).collect();
+
+    let as_shapes: Option<Vec<Shape>> = rows.iter().map(|(_, r)| game.player_shape(r)).collect();
+    let as_outcomes: Option<Vec<Shape>> = rows
+        .iter()
+        .map(|&(o, r)| Some(game.for_outcome(o, game.outcome_for_symbol(r)?)))
+        .collect();
+    let hindsight: Vec<Shape> = opponent
+        .iter()
+        .map(|&o| {
+            game.shapes()
+                .max_by_key(|&p| (game.round_score(p, o), std::cmp::Reverse(p)))
+                .unwrap()
+        })
+        .collect();
+
+    let mut guides = Vec::new();
+    if let Some(shapes) = as_shapes {
+        guides.push(model::tally(&game, "guide as shapes", &shapes, &opponent));
+    }
+    if let Some(shapes) = as_outcomes {
+        guides.push(model::tally(&game, "guide as outcomes", &shapes, &opponent));
+    }
+    let models: Vec<model::Evaluation> = (0..=order)
+        .map(|k| model::evaluate(&game, &opponent, k))
+        .collect();
+    let best_possible = model::tally(&game, "hindsight", &hindsight, &opponent);
+
+    println!("Day {DAY} opponent model over {} rounds:", opponent.len());
+    println!(
+        "{:<20} {:>9} {:>7} {:>6} {:>6} {:>6}",
+        "strategy", "expected", "actual", "wins", "draws", "losses"
+    );
+    for e in guides.iter().chain(&models).chain([&best_possible]) {
+        let expected = e.expected.map_or(String::from("-"), |x| format!("{x:.1}"));
+        println!(
+            "{:<20} {expected:>9} {:>7} {:>6} {:>6} {:>6}",
+            e.name, e.actual, e.wins, e.draws, e.losses
+        );
+    }
+}
+
+/// Args: `[rounds] [seed] [game]`, rounds per match (default 1000), the random
+/// seed (default 2022) and a game as for `part_game`
+pub fn part_tournament(args: &[String]) {
+    use tournament::{Constant, CopyLast, Cycle, Entrant, FollowGuide, FrequencyCounter, Random};
+
+    let rounds: usize = args
+        .first()
+        .map_or(1000, |n| n.parse().expect("rounds should be a number"));
+    let seed: u64 = args
+        .get(1)
+        .map_or(2022, |n| n.parse().expect("seed should be a number"));
+    let game =
+        Game::load(args.get(2).map_or("rps", |g| g.as_str())).unwrap_or_else(|e| panic!("{e}"));
+
+    let file_path = format!("src/input{DAY}.txt");
+    let contents =
+        fs::read_to_string(file_path).expect("Should have been able to read {DAY} the file");
+    let guide = guide_shapes(&game, &contents).unwrap_or_else(|e| panic!("{e}"));
+
+    let mut entrants: Vec<Entrant> = Vec::new();
+    for shape in game.shapes() {
+        entrants.push(Box::new(move || Box::new(Constant(shape))));
+    }
+    entrants.push(Box::new(|| Box::new(Cycle)));
+    entrants.push(Box::new(|| Box::new(CopyLast)));
+    entrants.push(Box::new(|| Box::new(Random)));
+    entrants.push(Box::new(|| Box::new(FrequencyCounter::new())));
+    if !guide.is_empty() {
+        entrants.push(Box::new(move || Box::new(FollowGuide(guide.clone()))));
+    }
+
+    let table = tournament::round_robin(&game, &entrants, rounds, seed);
+
+    println!(
+        "Day {DAY} {} tournament, {rounds} rounds a match, seed {seed}:",
+        game.name
+    );
+    println!(
+        "{:>4}  {:<20} {:>3} {:>3} {:>3} {:>4} {:>9} {:>9}",
+        "rank", "strategy", "W", "D", "L", "pts", "for", "against"
+    );
+    for (i, s) in table.iter().enumerate() {
+        println!(
+            "{:>4}  {:<20} {:>3} {:>3} {:>3} {:>4} {:>9} {:>9}",
+            i + 1,
+            s.name,
+            s.won,
+            s.drawn,
+            s.lost,
+            s.points(),
+            s.score_for,
+            s.score_against
+        );
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -129,4 +314,29 @@ mod tests {
     fn do_part_b() {
         part_b();
     }
+
+    #[test]
+    fn scores_any_game() {
+        let classic = Game::classic();
+        let sample = "A Y\nB X\nC Z\n";
+        assert_eq!(score_a(&classic, sample), Ok(15));
+        assert_eq!(score_b(&classic, sample), Ok(12));
+        assert_eq!(
+            score_a(&classic, "A Q"),
+            Err(ParseError::Shape(String::from("Q")))
+        );
+        assert_eq!(score_b(&classic, "A\n"), Err(ParseError::Line(1)));
+        assert_eq!(
+            guide_shapes(&classic, "A Y\nB Q\nC Z\n"),
+            Err(ParseError::Shape(String::from("Q")))
+        );
+
+        // Rock v Spock, Lizard v Paper, Spock v Scissors
+        let spock = Game::lizard_spock();
+        let sample = "A W\nB V\nC W\n";
+        assert_eq!(score_a(&spock, sample), Ok(11 + 10 + 11));
+        // Lose to Rock with Scissors, draw Paper, beat Scissors with Rock
+        let sample = "A X\nB Y\nC Z\n";
+        assert_eq!(score_b(&spock, sample), Ok(3 + 5 + 7));
+    }
 }
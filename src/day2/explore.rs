@@ -0,0 +1,143 @@
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+use super::game::{Game, Outcome, Shape};
+
+/// What the guide's right column symbols are taken to mean, in symbol order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Meaning {
+    Shapes(Vec<Shape>),
+    Outcomes(Vec<Outcome>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interpretation {
+    pub meaning: Meaning,
+    pub score: u32,
+    /// Matches the game's own symbols for shapes or outcomes
+    pub intended: bool,
+}
+
+impl Interpretation {
+    /// e.g. `X=Rock Y=Paper Z=Scissors`
+    pub fn describe(&self, game: &Game, symbols: &[&str]) -> String {
+        let names: Vec<String> = match &self.meaning {
+            Meaning::Shapes(shapes) => shapes
+                .iter()
+                .map(|&s| game.weapon(s).name.clone())
+                .collect(),
+            Meaning::Outcomes(outcomes) => outcomes.iter().map(|o| format!("{o:?}")).collect(),
+        };
+        let pairs: Vec<String> = symbols
+            .iter()
+            .zip(names)
+            .map(|(sym, name)| format!("{sym}={name}"))
+            .collect();
+        pairs.join(" ")
+    }
+}
+
+/// Every way to assign `k` items to distinct slots out of `n`
+fn injections(k: usize, n: usize) -> Vec<Vec<usize>> {
+    if k == 0 {
+        return vec![Vec::new()];
+    }
+    let mut results = Vec::new();
+    for prefix in injections(k - 1, n) {
+        for slot in (0..n).filter(|s| !prefix.contains(s)) {
+            let mut next = prefix.clone();
+            next.push(slot);
+            results.push(next);
+        }
+    }
+    results
+}
+
+/// Score the guide under every one-to-one reading of its right column symbols,
+/// as shapes and as outcomes, best first.
+///
+/// Also returns the distinct right column symbols, which the meanings are ordered by.
+pub fn explore<'a>(game: &Game, rows: &[(Shape, &'a str)]) -> (Vec<&'a str>, Vec<Interpretation>) {
+    // How often each opponent shape meets each symbol
+    let mut counts: BTreeMap<(&str, Shape), u32> = BTreeMap::new();
+    for &(opponent, symbol) in rows {
+        *counts.entry((symbol, opponent)).or_default() += 1;
+    }
+    let mut symbols: Vec<&str> = counts.keys().map(|(sym, _)| *sym).collect();
+    symbols.dedup();
+    let index = |sym: &str| symbols.iter().position(|s| *s == sym).unwrap();
+
+    let mut results = Vec::new();
+    for assignment in injections(symbols.len(), game.len()) {
+        let shapes: Vec<Shape> = assignment.into_iter().map(Shape).collect();
+        let score = counts
+            .iter()
+            .map(|(&(sym, opponent), n)| n * game.round_score(shapes[index(sym)], opponent))
+            .sum();
+        let intended = symbols
+            .iter()
+            .zip(&shapes)
+            .all(|(sym, &s)| game.weapon(s).player_symbol == *sym);
+        results.push(Interpretation {
+            meaning: Meaning::Shapes(shapes),
+            score,
+            intended,
+        });
+    }
+    for assignment in injections(symbols.len(), Outcome::ALL.len()) {
+        let outcomes: Vec<Outcome> = assignment.into_iter().map(|i| Outcome::ALL[i]).collect();
+        let score = counts
+            .iter()
+            .map(|(&(sym, opponent), n)| {
+                let player = game.for_outcome(opponent, outcomes[index(sym)]);
+                n * game.round_score(player, opponent)
+            })
+            .sum();
+        let intended = symbols
+            .iter()
+            .zip(&outcomes)
+            .all(|(sym, &o)| game.outcome_for_symbol(sym) == Some(o));
+        results.push(Interpretation {
+            meaning: Meaning::Outcomes(outcomes),
+            score,
+            intended,
+        });
+    }
+
+    // Stable, so shapes stay ahead of outcomes on ties
+    results.sort_by_key(|r| Reverse(r.score));
+    (symbols, results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn explores_every_reading() {
+        let game = Game::classic();
+        let rock = game.opponent_shape("A").unwrap();
+        let paper = game.opponent_shape("B").unwrap();
+        let scissors = game.opponent_shape("C").unwrap();
+        let rows = [(rock, "Y"), (paper, "X"), (scissors, "Z")];
+
+        let (symbols, results) = explore(&game, &rows);
+        assert_eq!(symbols, vec!["X", "Y", "Z"]);
+        assert_eq!(results.len(), 12);
+
+        let intended: Vec<u32> = results
+            .iter()
+            .filter(|r| r.intended)
+            .map(|r| r.score)
+            .collect();
+        assert_eq!(intended.len(), 2);
+        assert!(intended.contains(&15) && intended.contains(&12));
+
+        // Always winning with the highest scoring shape is the best possible
+        assert_eq!(results[0].score, 3 * 6 + 2 + 3 + 1);
+        assert_eq!(
+            results[0].describe(&game, &symbols),
+            "X=Scissors Y=Paper Z=Rock"
+        );
+    }
+}
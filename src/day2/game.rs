@@ -0,0 +1,354 @@
+use std::fmt;
+use std::fs;
+
+use super::ParseError;
+
+/// A shape, as an index into its game's weapons
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Shape(pub usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Outcome {
+    Lose,
+    Draw,
+    Win,
+}
+
+impl Outcome {
+    pub const ALL: [Outcome; 3] = [Outcome::Lose, Outcome::Draw, Outcome::Win];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Weapon {
+    pub name: String,
+    /// Symbol in the guide's left column
+    pub opponent_symbol: String,
+    /// Symbol in the guide's right column when it means a shape
+    pub player_symbol: String,
+    pub score: u32,
+}
+
+/// A hand game where each weapon beats the `(n - 1) / 2` weapons listed before it,
+/// wrapping around, and loses to the rest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    pub name: String,
+    pub weapons: Vec<Weapon>,
+    /// Right column symbol and score for each outcome, in `Outcome` order
+    pub outcomes: [(String, u32); 3],
+}
+
+const CLASSIC: &str = "\
+name Rock Paper Scissors
+lose X 0
+draw Y 3
+win Z 6
+weapon Rock A X 1
+weapon Paper B Y 2
+weapon Scissors C Z 3
+";
+
+const LIZARD_SPOCK: &str = "\
+name Rock Paper Scissors Lizard Spock
+lose X 0
+draw Y 3
+win Z 6
+weapon Rock A X 1
+weapon Spock E W 5
+weapon Paper B Y 2
+weapon Lizard D V 4
+weapon Scissors C Z 3
+";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DefinitionError {
+    Io(String),
+    /// 1 based line number and what is wrong with it
+    Line(usize, String),
+    /// Weapon count must be odd and at least 3
+    WeaponCount(usize),
+    MissingOutcome(Outcome),
+    DuplicateSymbol(String),
+}
+
+impl fmt::Display for DefinitionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DefinitionError::Io(e) => write!(f, "could not read game definition: {e}"),
+            DefinitionError::Line(n, message) => write!(f, "line {n}: {message}"),
+            DefinitionError::WeaponCount(n) => {
+                write!(
+                    f,
+                    "a game needs an odd number of weapons, at least 3, found {n}"
+                )
+            }
+            DefinitionError::MissingOutcome(o) => write!(f, "no symbol given for {o:?}"),
+            DefinitionError::DuplicateSymbol(s) => write!(f, "symbol {s:?} is used twice"),
+        }
+    }
+}
+
+impl Game {
+    pub fn classic() -> Game {
+        Game::from_definition(CLASSIC).unwrap()
+    }
+
+    pub fn lizard_spock() -> Game {
+        Game::from_definition(LIZARD_SPOCK).unwrap()
+    }
+
+    pub fn preset(name: &str) -> Option<Game> {
+        match name {
+            "rps" | "classic" => Some(Game::classic()),
+            "rpsls" | "lizard-spock" => Some(Game::lizard_spock()),
+            _ => None,
+        }
+    }
+
+    /// A preset by name, otherwise a definition file
+    pub fn load(name_or_path: &str) -> Result<Game, DefinitionError> {
+        match Game::preset(name_or_path) {
+            Some(game) => Ok(game),
+            None => {
+                let text = fs::read_to_string(name_or_path)
+                    .map_err(|e| DefinitionError::Io(format!("{name_or_path}: {e}")))?;
+                Game::from_definition(&text)
+            }
+        }
+    }
+
+    /// Parse a definition made of lines like these, with `#` comments:
+    ///
+    /// ```text
+    /// name Rock Paper Scissors
+    /// lose X 0
+    /// draw Y 3
+    /// win Z 6
+    /// weapon Rock A X 1
+    /// ```
+    ///
+    /// `weapon` lines give the name, opponent symbol, player symbol and score,
+    /// in dominance order.
+    pub fn from_definition(text: &str) -> Result<Game, DefinitionError> {
+        let mut name = String::new();
+        let mut weapons = Vec::new();
+        let mut outcomes: [Option<(String, u32)>; 3] = [None, None, None];
+
+        for (i, raw) in text.lines().enumerate() {
+            let bad = |message: &str| DefinitionError::Line(i + 1, message.to_string());
+            let line = raw.split('#').next().unwrap().trim();
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let score = |s: &str| {
+                s.parse::<u32>()
+                    .map_err(|_| bad("score should be a number"))
+            };
+
+            match parts.as_slice() {
+                [] => {}
+                ["name", ..] => name = parts[1..].join(" "),
+                [kind @ ("lose" | "draw" | "win"), symbol, points] => {
+                    let outcome = match *kind {
+                        "lose" => Outcome::Lose,
+                        "draw" => Outcome::Draw,
+                        _ => Outcome::Win,
+                    };
+                    outcomes[outcome.index()] = Some((symbol.to_string(), score(points)?));
+                }
+                ["weapon", weapon, opponent, player, points] => weapons.push(Weapon {
+                    name: weapon.to_string(),
+                    opponent_symbol: opponent.to_string(),
+                    player_symbol: player.to_string(),
+                    score: score(points)?,
+                }),
+                _ => return Err(bad(&format!("unrecognised line {raw:?}"))),
+            }
+        }
+
+        if weapons.len() < 3 || weapons.len() % 2 == 0 {
+            return Err(DefinitionError::WeaponCount(weapons.len()));
+        }
+        for symbols in [
+            weapons
+                .iter()
+                .map(|w| &w.opponent_symbol)
+                .collect::<Vec<_>>(),
+            weapons.iter().map(|w| &w.player_symbol).collect(),
+        ] {
+            for (i, s) in symbols.iter().enumerate() {
+                if symbols[..i].contains(s) {
+                    return Err(DefinitionError::DuplicateSymbol(s.to_string()));
+                }
+            }
+        }
+        let [lose, draw, win] = outcomes;
+        let outcomes = [
+            lose.ok_or(DefinitionError::MissingOutcome(Outcome::Lose))?,
+            draw.ok_or(DefinitionError::MissingOutcome(Outcome::Draw))?,
+            win.ok_or(DefinitionError::MissingOutcome(Outcome::Win))?,
+        ];
+        if outcomes[0].0 == outcomes[1].0 || outcomes[1].0 == outcomes[2].0 {
+            return Err(DefinitionError::DuplicateSymbol(outcomes[1].0.clone()));
+        }
+        if outcomes[0].0 == outcomes[2].0 {
+            return Err(DefinitionError::DuplicateSymbol(outcomes[0].0.clone()));
+        }
+
+        Ok(Game {
+            name,
+            weapons,
+            outcomes,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.weapons.len()
+    }
+
+    pub fn shapes(&self) -> impl Iterator<Item = Shape> {
+        (0..self.len()).map(Shape)
+    }
+
+    pub fn weapon(&self, shape: Shape) -> &Weapon {
+        &self.weapons[shape.0]
+    }
+
+    /// Whether `a` defeats `b`
+    pub fn beats(&self, a: Shape, b: Shape) -> bool {
+        let n = self.len();
+        let distance = (a.0 + n - b.0) % n;
+        (1..=n / 2).contains(&distance)
+    }
+
+    /// Whether `a` is defeated by `b`
+    pub fn loses_to(&self, a: Shape, b: Shape) -> bool {
+        self.beats(b, a)
+    }
+
+    /// How playing `player` against `opponent` turns out
+    pub fn outcome(&self, player: Shape, opponent: Shape) -> Outcome {
+        if self.beats(player, opponent) {
+            Outcome::Win
+        } else if self.loses_to(player, opponent) {
+            Outcome::Lose
+        } else {
+            Outcome::Draw
+        }
+    }
+
+    /// The nearest shape in dominance order to play against `opponent` to get `outcome`
+    pub fn for_outcome(&self, opponent: Shape, outcome: Outcome) -> Shape {
+        let n = self.len();
+        match outcome {
+            Outcome::Lose => Shape((opponent.0 + n - 1) % n),
+            Outcome::Draw => opponent,
+            Outcome::Win => Shape((opponent.0 + 1) % n),
+        }
+    }
+
+    pub fn outcome_score(&self, outcome: Outcome) -> u32 {
+        self.outcomes[outcome.index()].1
+    }
+
+    /// Score for one round from the player's side
+    pub fn round_score(&self, player: Shape, opponent: Shape) -> u32 {
+        self.weapon(player).score + self.outcome_score(self.outcome(player, opponent))
+    }
+
+    pub fn opponent_shape(&self, symbol: &str) -> Option<Shape> {
+        self.shapes()
+            .find(|&s| self.weapon(s).opponent_symbol == symbol)
+    }
+
+    pub fn player_shape(&self, symbol: &str) -> Option<Shape> {
+        self.shapes()
+            .find(|&s| self.weapon(s).player_symbol == symbol)
+    }
+
+    pub fn outcome_for_symbol(&self, symbol: &str) -> Option<Outcome> {
+        Outcome::ALL
+            .into_iter()
+            .find(|o| self.outcomes[o.index()].0 == symbol)
+    }
+
+    /// A left column symbol, which symbols mean which shape depends on the game
+    pub fn parse_opponent(&self, symbol: &str) -> Result<Shape, ParseError> {
+        self.opponent_shape(symbol)
+            .ok_or_else(|| ParseError::Shape(symbol.to_string()))
+    }
+
+    /// A right column symbol meaning the shape to play
+    pub fn parse_player(&self, symbol: &str) -> Result<Shape, ParseError> {
+        self.player_shape(symbol)
+            .ok_or_else(|| ParseError::Shape(symbol.to_string()))
+    }
+
+    /// A right column symbol meaning the outcome to aim for
+    pub fn parse_outcome(&self, symbol: &str) -> Result<Outcome, ParseError> {
+        self.outcome_for_symbol(symbol)
+            .ok_or_else(|| ParseError::Outcome(symbol.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dominance_is_balanced() {
+        for game in [Game::classic(), Game::lizard_spock()] {
+            for a in game.shapes() {
+                let wins = game.shapes().filter(|&b| game.beats(a, b)).count();
+                let losses = game.shapes().filter(|&b| game.beats(b, a)).count();
+                assert_eq!((wins, losses), (game.len() / 2, game.len() / 2));
+                for outcome in Outcome::ALL {
+                    assert_eq!(game.outcome(game.for_outcome(a, outcome), a), outcome);
+                }
+            }
+        }
+
+        let game = Game::lizard_spock();
+        let rock = game.player_shape("X").unwrap();
+        let spock = game.player_shape("W").unwrap();
+        let lizard = game.opponent_shape("D").unwrap();
+        assert!(game.beats(rock, lizard));
+        assert!(game.beats(spock, rock));
+        assert!(game.loses_to(rock, spock));
+        assert!(!game.loses_to(rock, lizard));
+        assert_eq!(game.round_score(spock, rock), 11);
+
+        let classic = Game::classic();
+        assert_eq!(classic.parse_opponent("B"), Ok(Shape(1)));
+        assert_eq!(classic.parse_player("Z"), Ok(Shape(2)));
+        assert_eq!(classic.parse_outcome("X"), Ok(Outcome::Lose));
+        assert_eq!(
+            classic.parse_player("W"),
+            Err(ParseError::Shape(String::from("W")))
+        );
+        assert_eq!(
+            classic.parse_outcome("A"),
+            Err(ParseError::Outcome(String::from("A")))
+        );
+    }
+
+    #[test]
+    fn rejects_bad_definitions() {
+        let even = "lose X 0\ndraw Y 3\nwin Z 6\nweapon A A X 1\nweapon B B Y 2\n";
+        assert_eq!(
+            Game::from_definition(even),
+            Err(DefinitionError::WeaponCount(2))
+        );
+        let missing = "weapon A A X 1\nweapon B B Y 2\nweapon C C Z 3\n";
+        assert_eq!(
+            Game::from_definition(missing),
+            Err(DefinitionError::MissingOutcome(Outcome::Lose))
+        );
+        assert!(matches!(
+            Game::from_definition("weapon Rock A X one"),
+            Err(DefinitionError::Line(1, _))
+        ));
+    }
+}
@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+
+use super::game::{Game, Outcome, Shape};
+
+/// Predicts the opponent's next shape from the ones it has already played.
+///
+/// Order 0 is a plain frequency model. Higher orders condition on the last
+/// `order` shapes, backing off to shorter contexts that haven't been seen yet.
+pub struct OpponentModel {
+    order: usize,
+    shapes: usize,
+    // Context of recent shapes -> how often each shape followed it
+    counts: HashMap<Vec<Shape>, Vec<u32>>,
+}
+
+impl OpponentModel {
+    pub fn new(game: &Game, order: usize) -> OpponentModel {
+        OpponentModel {
+            order,
+            shapes: game.len(),
+            counts: HashMap::new(),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self.order {
+            0 => String::from("frequency"),
+            k => format!("markov order {k}"),
+        }
+    }
+
+    /// Record that `next` followed `history`
+    pub fn observe(&mut self, history: &[Shape], next: Shape) {
+        for k in 0..=self.order.min(history.len()) {
+            let context = history[history.len() - k..].to_vec();
+            let counts = self
+                .counts
+                .entry(context)
+                .or_insert_with(|| vec![0; self.shapes]);
+            counts[next.0] += 1;
+        }
+    }
+
+    /// Probability of each shape coming next, with add-one smoothing
+    pub fn predict(&self, history: &[Shape]) -> Vec<f64> {
+        let longest = self.order.min(history.len());
+        let counts = (0..=longest)
+            .rev()
+            .find_map(|k| self.counts.get(&history[history.len() - k..]));
+
+        match counts {
+            Some(counts) => {
+                let total: u32 = counts.iter().sum();
+                counts
+                    .iter()
+                    .map(|&c| (c + 1) as f64 / (total as usize + self.shapes) as f64)
+                    .collect()
+            }
+            None => vec![1.0 / self.shapes as f64; self.shapes],
+        }
+    }
+}
+
+/// The shape with the best expected score against `prediction`, and that score
+pub fn best_response(game: &Game, prediction: &[f64]) -> (Shape, f64) {
+    let expected = |player: Shape| -> f64 {
+        game.shapes()
+            .map(|opponent| prediction[opponent.0] * game.round_score(player, opponent) as f64)
+            .sum()
+    };
+    game.shapes()
+        .map(|s| (s, expected(s)))
+        .fold(None, |best: Option<(Shape, f64)>, (s, e)| match best {
+            Some((_, b)) if b >= e => best,
+            _ => Some((s, e)),
+        })
+        .unwrap()
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Evaluation {
+    pub name: String,
+    /// Sum of each round's expected score under the model, if there is one
+    pub expected: Option<f64>,
+    pub actual: u32,
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+}
+
+impl Evaluation {
+    fn record(&mut self, game: &Game, player: Shape, opponent: Shape) {
+        self.actual += game.round_score(player, opponent);
+        match game.outcome(player, opponent) {
+            Outcome::Win => self.wins += 1,
+            Outcome::Draw => self.draws += 1,
+            Outcome::Lose => self.losses += 1,
+        }
+    }
+}
+
+/// Play the model's best response each round, only learning from earlier rounds
+pub fn evaluate(game: &Game, opponent: &[Shape], order: usize) -> Evaluation {
+    let mut model = OpponentModel::new(game, order);
+    let mut results = Evaluation {
+        name: model.name(),
+        ..Evaluation::default()
+    };
+
+    for (i, &actual) in opponent.iter().enumerate() {
+        let history = &opponent[..i];
+        let (player, expected) = best_response(game, &model.predict(history));
+        *results.expected.get_or_insert(0.0) += expected;
+        results.record(game, player, actual);
+        model.observe(history, actual);
+    }
+    results
+}
+
+/// Tally the shapes someone actually played
+pub fn tally(game: &Game, name: &str, player: &[Shape], opponent: &[Shape]) -> Evaluation {
+    let mut results = Evaluation {
+        name: name.to_string(),
+        ..Evaluation::default()
+    };
+    for (&p, &o) in player.iter().zip(opponent) {
+        results.record(game, p, o);
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn learns_a_cycle() {
+        let game = Game::classic();
+        let cycle: Vec<Shape> = (0..300).map(|i| Shape(i % 3)).collect();
+
+        // Frequency can't see the cycle, order 1 learns it after a few rounds
+        let frequency = evaluate(&game, &cycle, 0);
+        let markov = evaluate(&game, &cycle, 1);
+        assert!(markov.wins > 290);
+        assert!(markov.actual > frequency.actual);
+        assert!(markov.expected.unwrap() > frequency.expected.unwrap());
+    }
+
+    #[test]
+    fn predicts_with_backoff() {
+        let game = Game::classic();
+        let mut model = OpponentModel::new(&game, 2);
+        assert_eq!(model.predict(&[]), vec![1.0 / 3.0; 3]);
+
+        model.observe(&[], Shape(0));
+        model.observe(&[Shape(0)], Shape(0));
+        // Unseen context of two falls back to the context of one
+        let p = model.predict(&[Shape(1), Shape(0)]);
+        assert_eq!(p, vec![0.5, 0.25, 0.25]);
+        assert_eq!(best_response(&game, &p).0, Shape(1));
+    }
+}
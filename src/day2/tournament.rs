@@ -0,0 +1,258 @@
+use std::cmp::Reverse;
+
+use super::game::{Game, Shape};
+use super::model::{best_response, OpponentModel};
+
+/// Small deterministic generator (splitmix64), so tournaments replay exactly from a seed
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `0..n`
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+}
+
+pub trait Strategy {
+    fn name(&self, game: &Game) -> String;
+
+    /// Shape for the next round, given the shapes both players have already played
+    fn play(&mut self, game: &Game, mine: &[Shape], theirs: &[Shape], rng: &mut Rng) -> Shape;
+}
+
+/// Always plays the same shape
+pub struct Constant(pub Shape);
+
+impl Strategy for Constant {
+    fn name(&self, game: &Game) -> String {
+        format!("always {}", game.weapon(self.0).name)
+    }
+
+    fn play(&mut self, _: &Game, _: &[Shape], _: &[Shape], _: &mut Rng) -> Shape {
+        self.0
+    }
+}
+
+/// Steps through the shapes in dominance order
+pub struct Cycle;
+
+impl Strategy for Cycle {
+    fn name(&self, _: &Game) -> String {
+        String::from("cycle")
+    }
+
+    fn play(&mut self, game: &Game, mine: &[Shape], _: &[Shape], _: &mut Rng) -> Shape {
+        Shape(mine.len() % game.len())
+    }
+}
+
+/// Plays whatever the opponent played last round
+pub struct CopyLast;
+
+impl Strategy for CopyLast {
+    fn name(&self, _: &Game) -> String {
+        String::from("copy last")
+    }
+
+    fn play(&mut self, game: &Game, _: &[Shape], theirs: &[Shape], rng: &mut Rng) -> Shape {
+        match theirs.last() {
+            Some(&s) => s,
+            None => Shape(rng.below(game.len())),
+        }
+    }
+}
+
+/// Uniformly random
+pub struct Random;
+
+impl Strategy for Random {
+    fn name(&self, _: &Game) -> String {
+        String::from("random")
+    }
+
+    fn play(&mut self, game: &Game, _: &[Shape], _: &[Shape], rng: &mut Rng) -> Shape {
+        Shape(rng.below(game.len()))
+    }
+}
+
+/// Best response to the opponent's shape frequencies so far
+pub struct FrequencyCounter {
+    model: Option<OpponentModel>,
+}
+
+impl FrequencyCounter {
+    pub fn new() -> FrequencyCounter {
+        FrequencyCounter { model: None }
+    }
+}
+
+impl Strategy for FrequencyCounter {
+    fn name(&self, _: &Game) -> String {
+        String::from("frequency counter")
+    }
+
+    fn play(&mut self, game: &Game, _: &[Shape], theirs: &[Shape], _: &mut Rng) -> Shape {
+        let model = self
+            .model
+            .get_or_insert_with(|| OpponentModel::new(game, 0));
+        if let Some((&last, earlier)) = theirs.split_last() {
+            model.observe(earlier, last);
+        }
+        best_response(game, &model.predict(theirs)).0
+    }
+}
+
+/// Plays the shapes from a strategy guide in order, starting again when it runs out
+pub struct FollowGuide(pub Vec<Shape>);
+
+impl Strategy for FollowGuide {
+    fn name(&self, _: &Game) -> String {
+        String::from("follow the guide")
+    }
+
+    fn play(&mut self, _: &Game, mine: &[Shape], _: &[Shape], _: &mut Rng) -> Shape {
+        self.0[mine.len() % self.0.len()]
+    }
+}
+
+/// Builds a fresh strategy for each match
+pub type Entrant = Box<dyn Fn() -> Box<dyn Strategy>>;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Standing {
+    pub name: String,
+    pub won: u32,
+    pub drawn: u32,
+    pub lost: u32,
+    /// Round scores earned and conceded across all matches
+    pub score_for: u64,
+    pub score_against: u64,
+}
+
+impl Standing {
+    /// 3 for a match win, 1 for a draw
+    pub fn points(&self) -> u32 {
+        3 * self.won + self.drawn
+    }
+}
+
+/// Play `rounds` rounds between two fresh strategies, returning their total scores
+fn play_match(
+    game: &Game,
+    a: &mut dyn Strategy,
+    b: &mut dyn Strategy,
+    rounds: usize,
+    rng: &mut Rng,
+) -> (u64, u64) {
+    let mut a_moves = Vec::with_capacity(rounds);
+    let mut b_moves = Vec::with_capacity(rounds);
+    let (mut a_score, mut b_score) = (0, 0);
+    for _ in 0..rounds {
+        let a_shape = a.play(game, &a_moves, &b_moves, rng);
+        let b_shape = b.play(game, &b_moves, &a_moves, rng);
+        a_score += game.round_score(a_shape, b_shape) as u64;
+        b_score += game.round_score(b_shape, a_shape) as u64;
+        a_moves.push(a_shape);
+        b_moves.push(b_shape);
+    }
+    (a_score, b_score)
+}
+
+/// Every entrant plays every other once. The table is sorted best first.
+pub fn round_robin(game: &Game, entrants: &[Entrant], rounds: usize, seed: u64) -> Vec<Standing> {
+    let mut table: Vec<Standing> = entrants
+        .iter()
+        .map(|e| Standing {
+            name: e().name(game),
+            ..Standing::default()
+        })
+        .collect();
+
+    let mut match_number = 0;
+    for i in 0..entrants.len() {
+        for j in i + 1..entrants.len() {
+            // Each match gets its own stream so adding entrants doesn't reshuffle others
+            let mut rng =
+                Rng::new(seed ^ (match_number as u64).wrapping_mul(0xA24B_AED4_963E_E407));
+            match_number += 1;
+
+            let (mut a, mut b) = (entrants[i](), entrants[j]());
+            let (a_score, b_score) = play_match(game, a.as_mut(), b.as_mut(), rounds, &mut rng);
+
+            table[i].score_for += a_score;
+            table[i].score_against += b_score;
+            table[j].score_for += b_score;
+            table[j].score_against += a_score;
+            match a_score.cmp(&b_score) {
+                std::cmp::Ordering::Greater => {
+                    table[i].won += 1;
+                    table[j].lost += 1;
+                }
+                std::cmp::Ordering::Less => {
+                    table[j].won += 1;
+                    table[i].lost += 1;
+                }
+                std::cmp::Ordering::Equal => {
+                    table[i].drawn += 1;
+                    table[j].drawn += 1;
+                }
+            }
+        }
+    }
+
+    // Stable, so entry order breaks exact ties
+    table.sort_by_key(|s| {
+        (
+            Reverse(s.points()),
+            Reverse(s.score_for as i64 - s.score_against as i64),
+        )
+    });
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entrants(game: &Game) -> Vec<Entrant> {
+        let rock = game.player_shape("X").unwrap();
+        vec![
+            Box::new(move || Box::new(Constant(rock)) as Box<dyn Strategy>),
+            Box::new(|| Box::new(Cycle)),
+            Box::new(|| Box::new(CopyLast)),
+            Box::new(|| Box::new(Random)),
+            Box::new(|| Box::new(FrequencyCounter::new())),
+        ]
+    }
+
+    #[test]
+    fn tournament_is_deterministic() {
+        let game = Game::classic();
+        let first = round_robin(&game, &entrants(&game), 200, 7);
+        let again = round_robin(&game, &entrants(&game), 200, 7);
+        assert_eq!(first, again);
+
+        // Everyone plays everyone else once
+        for s in &first {
+            assert_eq!(s.won + s.drawn + s.lost, 4);
+        }
+        // Countering frequencies crushes a constant shape
+        let constant = first.iter().find(|s| s.name == "always Rock").unwrap();
+        assert!(constant.lost >= 1);
+        assert_eq!(first[0].name, "frequency counter");
+    }
+}
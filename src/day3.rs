@@ -1,94 +1,868 @@
-use std::collections::HashSet;
+mod priority;
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::fs;
-use std::str;
+
+use crate::bench;
+use crate::bitset::BitSet128;
+use priority::{PriorityScheme, Standard};
 
 const DAY: i32 = 3;
 
-struct Input<'a> {
-    lines: str::Lines<'a>,
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RucksackError {
+    /// A symbol the priority scheme doesn't know, at a 1 based column
+    InvalidItem {
+        line: usize,
+        column: usize,
+        item: char,
+    },
+    OddLength {
+        line: usize,
+        len: usize,
+    },
+    NoSharedItem {
+        line: usize,
+    },
+    SeveralSharedItems {
+        line: usize,
+        items: String,
+    },
+    /// A group of rucksacks, identified by its first line
+    NoBadge {
+        line: usize,
+    },
+    SeveralBadges {
+        line: usize,
+        items: String,
+    },
+    IncompleteGroup {
+        line: usize,
+        size: usize,
+    },
+    /// Groups of no rucksacks
+    EmptyGroups,
+    /// No choice of compartment for each item type gives two halves of the same size
+    CantRepack {
+        line: usize,
+    },
+    /// More item types than fit a bit set
+    TooManyItemTypes {
+        count: usize,
+    },
 }
-impl<'a> Iterator for Input<'a> {
-    type Item = (&'a str, &'a str);
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let line = self.lines.next();
-        line.map(|text| text.split_at(text.len() / 2))
+impl fmt::Display for RucksackError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RucksackError::InvalidItem { line, column, item } => {
+                write!(f, "line {line}, column {column}: {item:?} is not an item")
+            }
+            RucksackError::OddLength { line, len } => write!(
+                f,
+                "line {line}: {len} items can't be split into two equal compartments"
+            ),
+            RucksackError::NoSharedItem { line } => {
+                write!(f, "line {line}: no item is in both compartments")
+            }
+            RucksackError::SeveralSharedItems { line, items } => {
+                write!(
+                    f,
+                    "line {line}: several items are in both compartments: {items}"
+                )
+            }
+            RucksackError::NoBadge { line } => {
+                write!(
+                    f,
+                    "group at line {line}: no item is shared by every rucksack"
+                )
+            }
+            RucksackError::SeveralBadges { line, items } => write!(
+                f,
+                "group at line {line}: several items are shared by every rucksack: {items}"
+            ),
+            RucksackError::IncompleteGroup { line, size } => {
+                write!(f, "group at line {line}: only {size} rucksacks")
+            }
+            RucksackError::EmptyGroups => write!(f, "groups need at least one rucksack"),
+            RucksackError::CantRepack { line } => write!(
+                f,
+                "line {line}: items can't be repacked into equal compartments without sharing"
+            ),
+            RucksackError::TooManyItemTypes { count } => write!(
+                f,
+                "{count} item types, at most {} are supported",
+                BitSet128::CAPACITY
+            ),
+        }
     }
 }
 
-struct Input2<'a> {
-    lines: str::Lines<'a>,
+/// One error per line
+fn report(errors: &[RucksackError]) -> String {
+    let lines: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+    lines.join("\n")
 }
-impl<'a> Iterator for Input2<'a> {
-    type Item = (&'a str, &'a str, &'a str);
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let line = self.lines.next();
-        match line {
-            Some(text) => Some((text, self.lines.next().unwrap(), self.lines.next().unwrap())),
-            None => None,
+/// The item types of an input, numbered in priority order so rucksacks fit a bit set
+struct Items {
+    symbols: Vec<char>,
+    priorities: Vec<u32>,
+    // ASCII symbols skip the hash lookup, they're all most inputs use
+    ascii: [Option<u32>; 128],
+    index: HashMap<char, u32>,
+}
+
+impl Items {
+    /// Every symbol in `contents` the scheme knows about, others are left to `item_set`
+    fn new(scheme: &dyn PriorityScheme, contents: &str) -> Result<Items, Vec<RucksackError>> {
+        let mut seen_ascii = [false; 128];
+        let mut seen = HashSet::new();
+        let mut found: Vec<(u32, char)> = Vec::new();
+        for c in contents.lines().flat_map(str::chars) {
+            let new = if c.is_ascii() {
+                !std::mem::replace(&mut seen_ascii[c as usize], true)
+            } else {
+                seen.insert(c)
+            };
+            if !new {
+                continue;
+            }
+            if let Some(priority) = scheme.priority(c) {
+                found.push((priority, c));
+            }
+        }
+        found.sort_unstable();
+        if found.len() > BitSet128::CAPACITY as usize {
+            return Err(vec![RucksackError::TooManyItemTypes { count: found.len() }]);
+        }
+        let mut ascii = [None; 128];
+        for (i, &(_, c)) in found.iter().enumerate() {
+            if c.is_ascii() {
+                ascii[c as usize] = Some(i as u32);
+            }
+        }
+        Ok(Items {
+            ascii,
+            index: found
+                .iter()
+                .enumerate()
+                .map(|(i, &(_, c))| (c, i as u32))
+                .collect(),
+            symbols: found.iter().map(|&(_, c)| c).collect(),
+            priorities: found.iter().map(|&(p, _)| p).collect(),
+        })
+    }
+
+    fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    fn index(&self, symbol: char) -> Option<u32> {
+        if symbol.is_ascii() {
+            self.ascii[symbol as usize]
+        } else {
+            self.index.get(&symbol).copied()
         }
     }
+
+    fn symbol(&self, item: u32) -> char {
+        self.symbols[item as usize]
+    }
+
+    fn priority(&self, item: u32) -> u32 {
+        self.priorities[item as usize]
+    }
+
+    fn names(&self, items: BitSet128) -> String {
+        items.iter().map(|i| self.symbol(i)).collect()
+    }
 }
 
-fn byte_rank(b: &u8) -> u8 {
-    match b {
-        b'A'..=b'Z' => b - b'A' + 27,
-        b'a'..=b'z' => b - b'a' + 1,
-        _ => panic!("unknown!"),
+/// Items of one rucksack, checking every one is known. Columns are counted from `column`.
+fn item_set(
+    items: &Items,
+    line: usize,
+    column: usize,
+    text: &str,
+) -> Result<BitSet128, Vec<RucksackError>> {
+    let mut set = BitSet128::new();
+    let mut errors = Vec::new();
+    for (i, c) in text.chars().enumerate() {
+        match items.index(c) {
+            Some(item) => {
+                set.insert(item);
+            }
+            None => errors.push(RucksackError::InvalidItem {
+                line,
+                column: column + i,
+                item: c,
+            }),
+        }
+    }
+    if errors.is_empty() {
+        Ok(set)
+    } else {
+        Err(errors)
     }
 }
 
-pub fn part_a() {
-    let file_path = format!("src/input{DAY}.txt");
+/// Split a rucksack into its compartments, which hold the same number of items
+fn compartments(line: usize, text: &str) -> Result<(&str, &str), Vec<RucksackError>> {
+    let len = text.chars().count();
+    if !len.is_multiple_of(2) {
+        return Err(vec![RucksackError::OddLength { line, len }]);
+    }
+    let mid = text
+        .char_indices()
+        .nth(len / 2)
+        .map_or(text.len(), |(i, _)| i);
+    Ok(text.split_at(mid))
+}
 
-    let contents =
-        fs::read_to_string(file_path).expect("Should have been able to read {DAY} the file");
+/// Priority of the one item in both compartments of a rucksack
+fn shared_item(items: &Items, line: usize, text: &str) -> Result<u32, Vec<RucksackError>> {
+    let (pack_1, pack_2) = match compartments(line, text) {
+        Ok(packs) => packs,
+        Err(mut errors) => {
+            if let Err(e) = item_set(items, line, 1, text) {
+                errors.extend(e);
+            }
+            return Err(errors);
+        }
+    };
 
-    let it = Input {
-        lines: contents.lines(),
+    let shared = match (
+        item_set(items, line, 1, pack_1),
+        item_set(items, line, pack_1.chars().count() + 1, pack_2),
+    ) {
+        (Ok(p1_set), Ok(p2_set)) => p1_set & p2_set,
+        (p1, p2) => {
+            return Err([p1.err(), p2.err()]
+                .into_iter()
+                .flatten()
+                .flatten()
+                .collect());
+        }
     };
+    match shared.len() {
+        0 => Err(vec![RucksackError::NoSharedItem { line }]),
+        1 => Ok(items.priority(shared.first().unwrap())),
+        _ => Err(vec![RucksackError::SeveralSharedItems {
+            line,
+            items: items.names(shared),
+        }]),
+    }
+}
+
+/// Priority of the one item carried by every rucksack in a group starting at `line`
+fn badge(items: &Items, line: usize, group: &[&str]) -> Result<u32, Vec<RucksackError>> {
+    let mut shared: Option<BitSet128> = None;
+    let mut errors = Vec::new();
+    for (i, text) in group.iter().enumerate() {
+        match item_set(items, line + i, 1, text) {
+            Ok(set) => shared = Some(shared.map_or(set, |s| s & set)),
+            Err(e) => errors.extend(e),
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let shared = shared.unwrap_or_default();
+    match shared.len() {
+        0 => Err(vec![RucksackError::NoBadge { line }]),
+        1 => Ok(items.priority(shared.first().unwrap())),
+        _ => Err(vec![RucksackError::SeveralBadges {
+            line,
+            items: items.names(shared),
+        }]),
+    }
+}
+
+/// Add up the priorities, or collect every error
+fn total(
+    results: impl Iterator<Item = Result<u32, Vec<RucksackError>>>,
+) -> Result<u32, Vec<RucksackError>> {
+    let mut sum = 0;
+    let mut errors = Vec::new();
+    for r in results {
+        match r {
+            Ok(priority) => sum += priority,
+            Err(e) => errors.extend(e),
+        }
+    }
+    if errors.is_empty() {
+        Ok(sum)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Total priority of the item in both compartments of each rucksack
+fn duplicates_total(
+    scheme: &dyn PriorityScheme,
+    contents: &str,
+) -> Result<u32, Vec<RucksackError>> {
+    let items = Items::new(scheme, contents)?;
+    total(
+        contents
+            .lines()
+            .enumerate()
+            .map(|(i, text)| shared_item(&items, i + 1, text)),
+    )
+}
 
+fn duplicates_total_hashset(contents: &str) -> u32 {
     let mut results: u32 = 0;
-    for (pack_1, pack_2) in it {
-        let p1_set: HashSet<&u8> = HashSet::from_iter(pack_1.as_bytes());
-        let p2_set: HashSet<&u8> = HashSet::from_iter(pack_2.as_bytes());
+    for text in contents.lines() {
+        let (pack_1, pack_2) = text.split_at(text.len() / 2);
+        let p1_set: HashSet<char> = HashSet::from_iter(pack_1.chars());
+        let p2_set: HashSet<char> = HashSet::from_iter(pack_2.chars());
 
         let duplicate = *p1_set.intersection(&p2_set).next().unwrap();
 
-        results += byte_rank(duplicate) as u32;
+        results += Standard.priority(duplicate).unwrap();
     }
-    // Solution 7446
-    println!("Day {DAY}a best:\n{results}");
+    results
 }
 
-pub fn part_b() {
-    let file_path = format!("src/input{DAY}.txt");
+/// Total priority of the item shared by each group of `size` consecutive rucksacks
+fn badges_total(
+    scheme: &dyn PriorityScheme,
+    contents: &str,
+    size: usize,
+) -> Result<u32, Vec<RucksackError>> {
+    if size == 0 {
+        return Err(vec![RucksackError::EmptyGroups]);
+    }
+    let items = Items::new(scheme, contents)?;
+    let lines: Vec<&str> = contents.lines().collect();
+    total(lines.chunks(size).enumerate().map(|(i, group)| {
+        let line = i * size + 1;
+        if group.len() < size {
+            return Err(vec![RucksackError::IncompleteGroup {
+                line,
+                size: group.len(),
+            }]);
+        }
+        badge(&items, line, group)
+    }))
+}
 
-    let contents =
-        fs::read_to_string(file_path).expect("Should have been able to read {DAY} the file");
+/// Exchange of the items at two 0 based positions, one in each compartment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Swap {
+    left: usize,
+    right: usize,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Repack {
+    line: usize,
+    swaps: Vec<Swap>,
+    /// Total priority of every item moved
+    cost: u32,
+    fixed: String,
+}
+
+/// Fewest swaps leaving no item type in both compartments, the cheapest by priority
+/// when there's a tie.
+///
+/// Each item type ends up wholly in one compartment, so this picks the types to keep
+/// on the left: their counts must add up to half the rucksack, and every one of them
+/// on the right costs a swap. That's a knapsack over the input's item types.
+fn repack(items: &Items, line: usize, text: &str) -> Result<Repack, Vec<RucksackError>> {
+    let mut errors = compartments(line, text).err().unwrap_or_default();
+    errors.extend(item_set(items, line, 1, text).err().into_iter().flatten());
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    let symbols: Vec<usize> = text
+        .chars()
+        .map(|c| items.index(c).unwrap() as usize)
+        .collect();
+    let half = symbols.len() / 2;
+
+    // Count of each item type in the left and right compartments
+    let mut counts = vec![(0usize, 0usize); items.len()];
+    for &t in &symbols[..half] {
+        counts[t].0 += 1;
+    }
+    for &t in &symbols[half..] {
+        counts[t].1 += 1;
+    }
 
-    let it = Input2 {
-        lines: contents.lines(),
+    // For each number of items kept left: (swaps, change in cost, types kept left)
+    let mut best: Vec<Option<(usize, i64, BitSet128)>> = vec![None; half + 1];
+    best[0] = Some((0, 0, BitSet128::new()));
+    for (t, &(l, r)) in counts.iter().enumerate() {
+        let size = l + r;
+        if size == 0 || size > half {
+            continue;
+        }
+        // Keeping `t` left moves its `r` items in rather than its `l` items out
+        let delta = (r as i64 - l as i64) * items.priority(t as u32) as i64;
+        for k in (size..=half).rev() {
+            if let Some((swaps, cost, mut kept)) = best[k - size] {
+                kept.insert(t as u32);
+                let candidate = (swaps + r, cost + delta, kept);
+                if best[k].is_none_or(|(s, c, _)| (candidate.0, candidate.1) < (s, c)) {
+                    best[k] = Some(candidate);
+                }
+            }
+        }
+    }
+    let Some((_, _, kept)) = best[half] else {
+        return Err(vec![RucksackError::CantRepack { line }]);
     };
 
+    let outgoing = (0..half).filter(|&i| !kept.contains(symbols[i] as u32));
+    let incoming = (half..symbols.len()).filter(|&i| kept.contains(symbols[i] as u32));
+    let swaps: Vec<Swap> = outgoing
+        .zip(incoming)
+        .map(|(left, right)| Swap { left, right })
+        .collect();
+
+    let mut fixed: Vec<char> = text.chars().collect();
+    let mut cost = 0;
+    for s in &swaps {
+        cost += items.priority(symbols[s.left] as u32) + items.priority(symbols[s.right] as u32);
+        fixed.swap(s.left, s.right);
+    }
+    Ok(Repack {
+        line,
+        swaps,
+        cost,
+        fixed: fixed.into_iter().collect(),
+    })
+}
+
+/// Repack every rucksack, or collect every error
+fn repack_all(
+    scheme: &dyn PriorityScheme,
+    contents: &str,
+) -> Result<Vec<Repack>, Vec<RucksackError>> {
+    let items = Items::new(scheme, contents)?;
+    let mut results = Vec::new();
+    let mut errors = Vec::new();
+    for (i, text) in contents.lines().enumerate() {
+        match repack(&items, i + 1, text) {
+            Ok(r) => results.push(r),
+            Err(e) => errors.extend(e),
+        }
+    }
+    if errors.is_empty() {
+        Ok(results)
+    } else {
+        Err(errors)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum GroupingError {
+    Items(Vec<RucksackError>),
+    Uneven {
+        rucksacks: usize,
+        size: usize,
+    },
+    /// A rucksack that isn't in any group with exactly one shared item
+    Isolated {
+        line: usize,
+    },
+    NoPartition,
+    GaveUp {
+        steps: usize,
+    },
+}
+
+impl fmt::Display for GroupingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GroupingError::Items(errors) => write!(f, "{}", report(errors)),
+            GroupingError::Uneven { rucksacks, size } => write!(
+                f,
+                "{rucksacks} rucksacks can't be split into groups of {size}"
+            ),
+            GroupingError::Isolated { line } => {
+                write!(
+                    f,
+                    "line {line} isn't in any group with exactly one shared item"
+                )
+            }
+            GroupingError::NoPartition => {
+                write!(f, "no grouping gives every group exactly one shared item")
+            }
+            GroupingError::GaveUp { steps } => {
+                write!(f, "no grouping found within {steps} search steps")
+            }
+        }
+    }
+}
+
+/// A group of rucksacks, as 0 based line indexes, and its badge
+type Group = (Vec<usize>, char);
+
+/// Every group of `size` rucksacks sharing exactly one item
+fn candidate_groups(items: &Items, sets: &[BitSet128], size: usize) -> Vec<Group> {
+    fn extend(
+        items: &Items,
+        sets: &[BitSet128],
+        size: usize,
+        group: &mut Vec<usize>,
+        shared: BitSet128,
+        results: &mut Vec<Group>,
+    ) {
+        if group.len() == size {
+            if shared.len() == 1 {
+                results.push((group.clone(), items.symbol(shared.first().unwrap())));
+            }
+            return;
+        }
+        let start = group.last().map_or(0, |last| last + 1);
+        for next in start..sets.len() {
+            // Sharing nothing can't get better with more members
+            let narrowed = if group.is_empty() {
+                sets[next]
+            } else {
+                shared & sets[next]
+            };
+            if narrowed.is_empty() {
+                continue;
+            }
+            group.push(next);
+            extend(items, sets, size, group, narrowed, results);
+            group.pop();
+        }
+    }
+
+    let mut results = Vec::new();
+    extend(
+        items,
+        sets,
+        size,
+        &mut Vec::new(),
+        BitSet128::new(),
+        &mut results,
+    );
+    results
+}
+
+/// Exact cover search (Knuth's Algorithm X) choosing groups so every rucksack is in one.
+///
+/// Always branches on the rucksack with the fewest groups left, which keeps shuffled
+/// inputs with lots of decoy groups tractable.
+struct GroupSearch {
+    groups: Vec<Group>,
+    // Candidate group indexes each rucksack is in
+    by_rucksack: Vec<Vec<usize>>,
+    alive: Vec<bool>,
+    // Alive groups per rucksack
+    counts: Vec<usize>,
+    covered: Vec<bool>,
+    chosen: Vec<usize>,
+    steps: usize,
+    limit: usize,
+}
+
+impl GroupSearch {
+    fn new(rucksacks: usize, groups: Vec<Group>, limit: usize) -> GroupSearch {
+        let mut by_rucksack = vec![Vec::new(); rucksacks];
+        for (g, (members, _)) in groups.iter().enumerate() {
+            for &m in members {
+                by_rucksack[m].push(g);
+            }
+        }
+        GroupSearch {
+            counts: by_rucksack.iter().map(Vec::len).collect(),
+            alive: vec![true; groups.len()],
+            covered: vec![false; rucksacks],
+            groups,
+            by_rucksack,
+            chosen: Vec::new(),
+            steps: 0,
+            limit,
+        }
+    }
+
+    /// Cover a group's rucksacks, returning the groups that clashed with it
+    fn choose(&mut self, g: usize) -> Vec<usize> {
+        let mut removed = Vec::new();
+        for i in 0..self.groups[g].0.len() {
+            let m = self.groups[g].0[i];
+            self.covered[m] = true;
+            for &h in &self.by_rucksack[m] {
+                if self.alive[h] {
+                    self.alive[h] = false;
+                    removed.push(h);
+                    for &x in &self.groups[h].0 {
+                        self.counts[x] -= 1;
+                    }
+                }
+            }
+        }
+        removed
+    }
+
+    fn unchoose(&mut self, g: usize, removed: Vec<usize>) {
+        for h in removed {
+            self.alive[h] = true;
+            for &x in &self.groups[h].0 {
+                self.counts[x] += 1;
+            }
+        }
+        for &m in &self.groups[g].0 {
+            self.covered[m] = false;
+        }
+    }
+
+    /// `None` when out of steps
+    fn search(&mut self) -> Option<bool> {
+        self.steps += 1;
+        if self.steps > self.limit {
+            return None;
+        }
+        let next = (0..self.covered.len())
+            .filter(|&r| !self.covered[r])
+            .min_by_key(|&r| self.counts[r]);
+        let rucksack = match next {
+            Some(r) => r,
+            None => return Some(true),
+        };
+
+        let options: Vec<usize> = self.by_rucksack[rucksack]
+            .iter()
+            .copied()
+            .filter(|&g| self.alive[g])
+            .collect();
+        for g in options {
+            let removed = self.choose(g);
+            self.chosen.push(g);
+            if self.search()? {
+                return Some(true);
+            }
+            self.chosen.pop();
+            self.unchoose(g, removed);
+        }
+        Some(false)
+    }
+}
+
+/// Split rucksacks in any order into groups of `size` that each share exactly one badge item
+fn discover_groups(
+    scheme: &dyn PriorityScheme,
+    contents: &str,
+    size: usize,
+    limit: usize,
+) -> Result<Vec<Group>, GroupingError> {
+    let items = Items::new(scheme, contents).map_err(GroupingError::Items)?;
+    let sets = contents
+        .lines()
+        .enumerate()
+        .map(|(i, text)| item_set(&items, i + 1, 1, text))
+        .collect::<Result<Vec<BitSet128>, _>>()
+        .map_err(GroupingError::Items)?;
+
+    if size == 0 || !sets.len().is_multiple_of(size) {
+        return Err(GroupingError::Uneven {
+            rucksacks: sets.len(),
+            size,
+        });
+    }
+
+    let mut search = GroupSearch::new(sets.len(), candidate_groups(&items, &sets, size), limit);
+    if let Some(r) = search.counts.iter().position(|&c| c == 0) {
+        return Err(GroupingError::Isolated { line: r + 1 });
+    }
+    match search.search() {
+        Some(true) => {
+            let mut groups: Vec<Group> = search
+                .chosen
+                .iter()
+                .map(|&g| search.groups[g].clone())
+                .collect();
+            groups.sort();
+            Ok(groups)
+        }
+        Some(false) => Err(GroupingError::NoPartition),
+        None => Err(GroupingError::GaveUp { steps: limit }),
+    }
+}
+
+fn badges_total_hashset(contents: &str) -> u32 {
+    let lines: Vec<&str> = contents.lines().collect();
+
     let mut results: u32 = 0;
-    for (pack_1, pack_2, pack_3) in it {
-        let p1_set: HashSet<&u8> = HashSet::from_iter(pack_1.as_bytes());
-        let p2_set: HashSet<&u8> = HashSet::from_iter(pack_2.as_bytes());
-        let p3_set: HashSet<&u8> = HashSet::from_iter(pack_3.as_bytes());
+    for group in lines.chunks(3) {
+        let p1_set: HashSet<char> = HashSet::from_iter(group[0].chars());
+        let p2_set: HashSet<char> = HashSet::from_iter(group[1].chars());
+        let p3_set: HashSet<char> = HashSet::from_iter(group[2].chars());
 
         let duplicate = *p1_set
             .intersection(&p2_set)
-            .find(|x| p3_set.contains(**x))
+            .find(|x| p3_set.contains(x))
             .unwrap();
 
-        results += byte_rank(duplicate) as u32;
+        results += Standard.priority(duplicate).unwrap();
     }
+    results
+}
+
+pub fn part_a() {
+    let file_path = format!("src/input{DAY}.txt");
+
+    let contents =
+        fs::read_to_string(file_path).expect("Should have been able to read {DAY} the file");
+
+    let results =
+        duplicates_total(&Standard, &contents).unwrap_or_else(|e| panic!("{}", report(&e)));
+    // Solution 7446
+    println!("Day {DAY}a best:\n{results}");
+}
+
+pub fn part_b() {
+    let file_path = format!("src/input{DAY}.txt");
+
+    let contents =
+        fs::read_to_string(file_path).expect("Should have been able to read {DAY} the file");
+
+    let results =
+        badges_total(&Standard, &contents, 3).unwrap_or_else(|e| panic!("{}", report(&e)));
     // Solution 2646
     println!("Day {DAY}b best:\n{results}");
 }
 
+/// Most steps the discover mode searches before giving up
+const SEARCH_STEPS: usize = 10_000_000;
+
+/// Args: `[size] [ordered|discover] [scheme]`, rucksacks per group (default 3),
+/// whether groups are consecutive lines or have to be found (default ordered) and
+/// priorities as for `part_scheme`
+pub fn part_groups(args: &[String]) {
+    let size: usize = args
+        .first()
+        .map_or(3, |n| n.parse().expect("size should be a number"));
+    let mode = args.get(1).map_or("ordered", |m| m.as_str());
+    let scheme = priority::load(args.get(2).map_or("standard", |s| s.as_str()))
+        .unwrap_or_else(|e| panic!("{e}"));
+
+    let file_path = format!("src/input{DAY}.txt");
+
+    let contents =
+        fs::read_to_string(file_path).expect("Should have been able to read {DAY} the file");
+
+    let results = match mode {
+        "ordered" => badges_total(scheme.as_ref(), &contents, size).map_err(GroupingError::Items),
+        "discover" => {
+            discover_groups(scheme.as_ref(), &contents, size, SEARCH_STEPS).map(|groups| {
+                for (members, badge) in &groups {
+                    let lines: Vec<String> = members.iter().map(|m| (m + 1).to_string()).collect();
+                    println!("Lines {} share {badge}", lines.join(", "));
+                }
+                groups
+                    .iter()
+                    .map(|(_, badge)| scheme.priority(*badge).unwrap())
+                    .sum()
+            })
+        }
+        _ => panic!("mode should be ordered or discover"),
+    };
+    match results {
+        Ok(total) => println!("Day {DAY} groups of {size} ({mode}):\n{total}"),
+        Err(e) => eprintln!("Day {DAY} groups of {size} ({mode}) failed:\n{e}"),
+    }
+}
+
+/// Args: `[rucksacks] [scheme]`, the file to repack (default the puzzle input) and
+/// priorities as for `part_scheme`
+pub fn part_repack(args: &[String]) {
+    let file_path = args
+        .first()
+        .cloned()
+        .unwrap_or_else(|| format!("src/input{DAY}.txt"));
+    let scheme = priority::load(args.get(1).map_or("standard", |s| s.as_str()))
+        .unwrap_or_else(|e| panic!("{e}"));
+
+    let contents =
+        fs::read_to_string(file_path).expect("Should have been able to read {DAY} the file");
+
+    let plans = repack_all(scheme.as_ref(), &contents).unwrap_or_else(|e| panic!("{}", report(&e)));
+    for plan in plans.iter().filter(|p| !p.swaps.is_empty()) {
+        let text: Vec<char> = contents
+            .lines()
+            .nth(plan.line - 1)
+            .unwrap()
+            .chars()
+            .collect();
+        let swaps: Vec<String> = plan
+            .swaps
+            .iter()
+            .map(|s| {
+                format!(
+                    "{}:{}<->{}:{}",
+                    s.left + 1,
+                    text[s.left],
+                    s.right + 1,
+                    text[s.right]
+                )
+            })
+            .collect();
+        println!("Line {}: {} -> {}", plan.line, swaps.join(" "), plan.fixed);
+    }
+    let moves: usize = plans.iter().map(|p| p.swaps.len()).sum();
+    let cost: u32 = plans.iter().map(|p| p.cost).sum();
+    println!(
+        "Day {DAY} repack of {} rucksacks:\n{moves} swaps, priority cost {cost}",
+        plans.len()
+    );
+}
+
+/// Args: `[scheme] [rucksacks]`, priorities by name (`standard`, `nocase`) or from a
+/// mapping file of `item priority` lines (default standard), and the file to score
+/// (default the puzzle input)
+pub fn part_scheme(args: &[String]) {
+    let scheme = priority::load(args.first().map_or("standard", |s| s.as_str()))
+        .unwrap_or_else(|e| panic!("{e}"));
+    let file_path = args
+        .get(1)
+        .cloned()
+        .unwrap_or_else(|| format!("src/input{DAY}.txt"));
+
+    let contents =
+        fs::read_to_string(file_path).expect("Should have been able to read {DAY} the file");
+
+    let duplicates =
+        duplicates_total(scheme.as_ref(), &contents).unwrap_or_else(|e| panic!("{}", report(&e)));
+    let badges =
+        badges_total(scheme.as_ref(), &contents, 3).unwrap_or_else(|e| panic!("{}", report(&e)));
+
+    println!("Day {DAY} priorities:");
+    println!("Duplicates: {duplicates}");
+    println!("Badges: {badges}");
+}
+
+/// Compare the hash set and bit set implementations
+pub fn part_bench() {
+    let file_path = format!("src/input{DAY}.txt");
+
+    let contents =
+        fs::read_to_string(file_path).expect("Should have been able to read {DAY} the file");
+
+    bench::compare(
+        "Day 3a HashSet -> BitSet128",
+        200,
+        || duplicates_total_hashset(&contents),
+        || duplicates_total(&Standard, &contents),
+    );
+    bench::compare(
+        "Day 3b HashSet -> BitSet128",
+        200,
+        || badges_total_hashset(&contents),
+        || badges_total(&Standard, &contents, 3),
+    );
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -102,4 +876,157 @@ mod tests {
     fn do_part_b() {
         part_b();
     }
+
+    #[test]
+    fn bitset_matches_hashset() {
+        let contents = fs::read_to_string("src/input3s.txt").unwrap();
+        assert_eq!(duplicates_total(&Standard, &contents), Ok(157));
+        assert_eq!(duplicates_total_hashset(&contents), 157);
+        assert_eq!(badges_total(&Standard, &contents, 3), Ok(70));
+        assert_eq!(badges_total_hashset(&contents), 70);
+    }
+
+    #[test]
+    fn reports_bad_rucksacks() {
+        let contents = "abcab\nab1a\nabcd\nabab\naBé\n";
+        assert_eq!(
+            duplicates_total(&Standard, contents),
+            Err(vec![
+                RucksackError::OddLength { line: 1, len: 5 },
+                RucksackError::InvalidItem {
+                    line: 2,
+                    column: 3,
+                    item: '1'
+                },
+                RucksackError::NoSharedItem { line: 3 },
+                RucksackError::SeveralSharedItems {
+                    line: 4,
+                    items: String::from("ab")
+                },
+                RucksackError::OddLength { line: 5, len: 3 },
+                RucksackError::InvalidItem {
+                    line: 5,
+                    column: 3,
+                    item: 'é'
+                },
+            ])
+        );
+
+        assert_eq!(
+            badges_total(&Standard, "ab\nbc\nca\nab\nab\nab\nzz\n", 3),
+            Err(vec![
+                RucksackError::NoBadge { line: 1 },
+                RucksackError::SeveralBadges {
+                    line: 4,
+                    items: String::from("ab")
+                },
+                RucksackError::IncompleteGroup { line: 7, size: 1 },
+            ])
+        );
+    }
+
+    #[test]
+    fn discovers_shuffled_groups() {
+        let contents = fs::read_to_string("src/input3s.txt").unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        let shuffled = [lines[3], lines[0], lines[5], lines[1], lines[4], lines[2]].join("\n");
+
+        let groups = discover_groups(&Standard, &shuffled, 3, 1000).unwrap();
+        let badges: u32 = groups
+            .iter()
+            .map(|(_, badge)| Standard.priority(*badge).unwrap())
+            .sum();
+        assert_eq!(badges, 70);
+        assert_eq!(groups[0].0, vec![0, 2, 4]);
+
+        assert_eq!(
+            discover_groups(&Standard, &shuffled, 4, 1000),
+            Err(GroupingError::Uneven {
+                rucksacks: 6,
+                size: 4
+            })
+        );
+        assert_eq!(
+            discover_groups(&Standard, "ab\nbc\nbd\nxy\nxz\nwv", 3, 1000),
+            Err(GroupingError::Isolated { line: 4 })
+        );
+        // Every rucksack is in some group, but they can't all be used at once
+        assert_eq!(
+            discover_groups(&Standard, "a\na\nb\nc\nbc\nabc", 3, 1000),
+            Err(GroupingError::NoPartition)
+        );
+    }
+
+    #[test]
+    fn groups_of_zero() {
+        let contents = "ab\nbc\nca\n";
+        assert_eq!(
+            badges_total(&Standard, contents, 0),
+            Err(vec![RucksackError::EmptyGroups])
+        );
+        assert_eq!(
+            discover_groups(&Standard, contents, 0, 1000),
+            Err(GroupingError::Uneven {
+                rucksacks: 3,
+                size: 0
+            })
+        );
+    }
+
+    #[test]
+    fn repacks_rucksacks() {
+        let contents = fs::read_to_string("src/input3s.txt").unwrap();
+        let plans = repack_all(&Standard, &contents).unwrap();
+        // A shared type with several copies may need a swap for each
+        let moves: usize = plans.iter().map(|p| p.swaps.len()).sum();
+        assert_eq!(moves, 9);
+        let items = Items::new(&Standard, &contents).unwrap();
+        for plan in plans {
+            let (left, right) = compartments(plan.line, &plan.fixed).unwrap();
+            assert_eq!(
+                item_set(&items, 0, 1, left).unwrap() & item_set(&items, 0, 1, right).unwrap(),
+                BitSet128::new()
+            );
+        }
+
+        // Moving a and b costs 3, moving c rather than a would cost 5
+        let items = Items::new(&Standard, "abcd").unwrap();
+        let plan = repack(&items, 1, "abac").unwrap();
+        assert_eq!(plan.swaps, vec![Swap { left: 1, right: 2 }]);
+        assert_eq!(plan.fixed, "aabc");
+        assert_eq!(plan.cost, 3);
+        assert_eq!(repack(&items, 1, "abcd").unwrap().swaps, vec![]);
+        assert_eq!(
+            repack_all(&Standard, "aaab\nabc\na1b\n"),
+            Err(vec![
+                RucksackError::CantRepack { line: 1 },
+                RucksackError::OddLength { line: 2, len: 3 },
+                RucksackError::OddLength { line: 3, len: 3 },
+                RucksackError::InvalidItem {
+                    line: 3,
+                    column: 2,
+                    item: '1'
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn other_schemes() {
+        let contents = fs::read_to_string("src/input3s.txt").unwrap();
+        // p, L, P, v, t, s: only L and P drop from 38 and 42 to 12 and 16
+        assert_eq!(
+            duplicates_total(&priority::CaseInsensitive, &contents),
+            Ok(157 - 26 * 2)
+        );
+
+        let fruit = priority::Mapping::from_text("🍎 1\n🍌 2\n🍒 3\né 4").unwrap();
+        let contents = "🍎🍌🍒🍎\né🍌🍒é\n🍒🍎é🍒\n";
+        assert_eq!(duplicates_total(&fruit, contents), Ok(1 + 4 + 3));
+        assert_eq!(badges_total(&fruit, contents, 3), Ok(3));
+        // Every symbol is unknown to the standard scheme
+        assert_eq!(duplicates_total(&Standard, contents).unwrap_err().len(), 12);
+        let plans = repack_all(&fruit, contents).unwrap();
+        assert_eq!(plans[0].fixed, "🍎🍎🍒🍌");
+    }
 }
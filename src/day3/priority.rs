@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+
+/// How much each item type matters
+pub trait PriorityScheme {
+    /// `None` when the symbol isn't an item under this scheme
+    fn priority(&self, item: char) -> Option<u32>;
+}
+
+/// The puzzle's scheme, a to z are 1 to 26 and A to Z are 27 to 52
+pub struct Standard;
+
+impl PriorityScheme for Standard {
+    fn priority(&self, item: char) -> Option<u32> {
+        match item {
+            'a'..='z' => Some(item as u32 - 'a' as u32 + 1),
+            'A'..='Z' => Some(item as u32 - 'A' as u32 + 27),
+            _ => None,
+        }
+    }
+}
+
+/// Both cases of a letter share its priority, 1 to 26
+pub struct CaseInsensitive;
+
+impl PriorityScheme for CaseInsensitive {
+    fn priority(&self, item: char) -> Option<u32> {
+        Standard.priority(item.to_ascii_lowercase())
+    }
+}
+
+/// Priorities of any symbols, read from a file
+pub struct Mapping {
+    priorities: HashMap<char, u32>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum MappingError {
+    Io(String),
+    /// 1 based line number and what is wrong with it
+    Line(usize, String),
+    DuplicateItem(char),
+}
+
+impl fmt::Display for MappingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MappingError::Io(e) => write!(f, "could not read priorities: {e}"),
+            MappingError::Line(n, message) => write!(f, "line {n}: {message}"),
+            MappingError::DuplicateItem(c) => write!(f, "item {c:?} is given twice"),
+        }
+    }
+}
+
+impl Mapping {
+    /// Parse lines of an item symbol and its priority. Lines starting with `#` are
+    /// comments, so `#` itself is written `\#`:
+    ///
+    /// ```text
+    /// # fruit
+    /// 🍎 1
+    /// 🍌 2
+    /// \# 3
+    /// ```
+    pub fn from_text(text: &str) -> Result<Mapping, MappingError> {
+        let mut priorities = HashMap::new();
+        for (i, line) in text.lines().enumerate() {
+            if line.trim_start().starts_with('#') {
+                continue;
+            }
+            let mut words = line.split_whitespace();
+            let (item, priority) = match (words.next(), words.next(), words.next()) {
+                (None, _, _) => continue,
+                (Some(item), Some(priority), None) => (item, priority),
+                _ => {
+                    return Err(MappingError::Line(
+                        i + 1,
+                        String::from("expected an item and a priority"),
+                    ));
+                }
+            };
+
+            // A backslash escapes the symbol after it
+            let mut chars = item
+                .strip_prefix('\\')
+                .filter(|s| !s.is_empty())
+                .unwrap_or(item)
+                .chars();
+            let item = match (chars.next(), chars.next()) {
+                (Some(c), None) => c,
+                _ => {
+                    return Err(MappingError::Line(
+                        i + 1,
+                        format!("{item:?} should be a single symbol"),
+                    ));
+                }
+            };
+            let priority = priority.parse().map_err(|_| {
+                MappingError::Line(i + 1, format!("{priority:?} is not a priority"))
+            })?;
+            if priorities.insert(item, priority).is_some() {
+                return Err(MappingError::DuplicateItem(item));
+            }
+        }
+        Ok(Mapping { priorities })
+    }
+}
+
+impl PriorityScheme for Mapping {
+    fn priority(&self, item: char) -> Option<u32> {
+        self.priorities.get(&item).copied()
+    }
+}
+
+/// A scheme by name (`standard`, `nocase`), otherwise a mapping file
+pub fn load(name_or_path: &str) -> Result<Box<dyn PriorityScheme>, MappingError> {
+    match name_or_path {
+        "standard" | "aoc" => Ok(Box::new(Standard)),
+        "nocase" | "case-insensitive" => Ok(Box::new(CaseInsensitive)),
+        _ => {
+            let text = fs::read_to_string(name_or_path)
+                .map_err(|e| MappingError::Io(format!("{name_or_path}: {e}")))?;
+            Ok(Box::new(Mapping::from_text(&text)?))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schemes() {
+        assert_eq!(Standard.priority('a'), Some(1));
+        assert_eq!(Standard.priority('Z'), Some(52));
+        assert_eq!(Standard.priority('é'), None);
+        assert_eq!(CaseInsensitive.priority('Z'), Some(26));
+
+        let mapping = Mapping::from_text("# fruit\n🍎 1\né 30\n\\# 5\n\\ 6\n").unwrap();
+        assert_eq!(mapping.priority('🍎'), Some(1));
+        assert_eq!(mapping.priority('é'), Some(30));
+        assert_eq!(mapping.priority('#'), Some(5));
+        assert_eq!(mapping.priority('\\'), Some(6));
+        assert_eq!(mapping.priority('a'), None);
+        assert_eq!(
+            Mapping::from_text("é 30 # accented").err(),
+            Some(MappingError::Line(
+                1,
+                String::from("expected an item and a priority")
+            ))
+        );
+
+        assert_eq!(
+            Mapping::from_text("ab 1").err(),
+            Some(MappingError::Line(
+                1,
+                String::from("\"ab\" should be a single symbol")
+            ))
+        );
+        assert_eq!(
+            Mapping::from_text("a 1\na 2").err(),
+            Some(MappingError::DuplicateItem('a'))
+        );
+    }
+}
@@ -1,14 +1,18 @@
-use std::cmp;
+mod graph;
+mod reassign;
+mod sweep;
+
 use std::fs;
 use std::str;
 
+use crate::interval::{Interval, IntervalSet};
+use graph::{Elf, OverlapGraph};
+
 const DAY: i32 = 4;
 
+/// Assignments of the elves on one line, any number of them
 struct InputRow {
-    e1_start: u32,
-    e1_end: u32,
-    e2_start: u32,
-    e2_end: u32,
+    ranges: Vec<Interval>,
 }
 struct Input<'a> {
     lines: str::Lines<'a>,
@@ -23,81 +27,256 @@ impl<'a> Iterator for Input<'a> {
                 if text.is_empty() {
                     return None;
                 }
-                println!("{text}");
-                let v: Vec<&str> = text.split(|c| c == ',' || c == '-').collect();
-                Some(InputRow {
-                    e1_start: v[0].parse::<u32>().unwrap(),
-                    e1_end: v[1].parse::<u32>().unwrap(),
-                    e2_start: v[2].parse::<u32>().unwrap(),
-                    e2_end: v[3].parse::<u32>().unwrap(),
-                })
+                let ranges = text
+                    .split(',')
+                    .map(|r| {
+                        let (start, end) = r.trim().split_once('-').unwrap();
+                        Interval::new(start.parse().unwrap(), end.parse().unwrap())
+                    })
+                    .collect();
+                Some(InputRow { ranges })
             }
             None => None,
         }
     }
 }
 
-struct Input2<'a> {
-    lines: str::Lines<'a>,
+pub fn part_a() {
+    let file_path = format!("src/input{DAY}.txt");
+
+    let contents =
+        fs::read_to_string(file_path).expect("Should have been able to read {DAY} the file");
+
+    let it = Input {
+        lines: contents.lines(),
+    };
+
+    let results = it
+        .filter(|row| {
+            graph::relations(&row.ranges)
+                .iter()
+                .any(|(_, _, r)| *r != graph::Relation::Overlaps)
+        })
+        .count();
+    // Solution 651
+    println!("Day {DAY}a best:\n{results}");
 }
-impl<'a> Iterator for Input2<'a> {
-    type Item = (&'a str, &'a str, &'a str);
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let line = self.lines.next();
-        match line {
-            Some(text) => Some((text, self.lines.next().unwrap(), self.lines.next().unwrap())),
-            None => None,
-        }
+pub fn part_b() {
+    let file_path = format!("src/input{DAY}.txt");
+
+    let contents =
+        fs::read_to_string(file_path).expect("Should have been able to read {DAY} the file");
+
+    let it = Input {
+        lines: contents.lines(),
+    };
+
+    let results = it
+        .filter(|row| !graph::relations(&row.ranges).is_empty())
+        .count();
+    // Solution 956
+    println!("Day {DAY}b best:\n{results}");
+}
+
+/// Section IDs from 1 up to the highest assigned section that no elf covers
+fn uncovered(contents: &str) -> IntervalSet {
+    let it = Input {
+        lines: contents.lines(),
+    };
+
+    let covered: IntervalSet = it.flat_map(|row| row.ranges).collect();
+    match covered.hull() {
+        Some(hull) => covered
+            .gaps_within(Interval::new(1, hull.end))
+            .into_iter()
+            .collect(),
+        None => IntervalSet::new(),
     }
 }
 
-pub fn part_a() {
+pub fn part_gaps() {
     let file_path = format!("src/input{DAY}.txt");
 
     let contents =
         fs::read_to_string(file_path).expect("Should have been able to read {DAY} the file");
 
+    let gaps = uncovered(&contents);
+    let count = gaps.coverage_len();
+    let listing: Vec<String> = gaps.intervals().iter().map(Interval::to_string).collect();
+
+    println!("Day {DAY} uncovered sections:\n{count}");
+    if !listing.is_empty() {
+        println!("{}", listing.join(", "));
+    }
+}
+
+/// Every elf in the input, in line order
+fn elves(contents: &str) -> Vec<Elf> {
     let it = Input {
         lines: contents.lines(),
     };
 
-    let mut results: u32 = 0;
-    for row in it {
-        let overlap_start = cmp::max(row.e1_start, row.e2_start);
-        let overlap_end = cmp::min(row.e1_end, row.e2_end);
+    it.enumerate()
+        .flat_map(|(i, row)| {
+            row.ranges
+                .into_iter()
+                .enumerate()
+                .map(move |(j, sections)| Elf {
+                    line: i + 1,
+                    position: j + 1,
+                    sections,
+                })
+        })
+        .collect()
+}
+
+/// Args: `[assignments]`, the file to analyse (default the puzzle input)
+pub fn part_graph(args: &[String]) {
+    let file_path = args
+        .first()
+        .cloned()
+        .unwrap_or_else(|| format!("src/input{DAY}.txt"));
 
-        if (overlap_start == row.e1_start && overlap_end == row.e1_end)
-            || (overlap_start == row.e2_start && overlap_end == row.e2_end)
-        {
-            results += 1
+    let contents =
+        fs::read_to_string(file_path).expect("Should have been able to read {DAY} the file");
+
+    let it = Input {
+        lines: contents.lines(),
+    };
+    for (i, row) in it.enumerate() {
+        let relations: Vec<String> = graph::relations(&row.ranges)
+            .iter()
+            .map(|(a, b, r)| format!("{} {r} {}", a + 1, b + 1))
+            .collect();
+        if !relations.is_empty() {
+            println!("Line {}: {}", i + 1, relations.join(", "));
         }
     }
-    // Solution 651
-    println!("Day {DAY}a best:\n{results}");
+
+    let graph = OverlapGraph::new(elves(&contents));
+    println!(
+        "Day {DAY} overlap graph:\n{} elves, {} overlapping pairs",
+        graph.elves.len(),
+        graph.edge_count()
+    );
+    if let Some((members, common)) = graph.largest_clique() {
+        let mut listing: Vec<String> = members
+            .iter()
+            .take(10)
+            .map(|&e| graph.elves[e].to_string())
+            .collect();
+        if members.len() > listing.len() {
+            listing.push(format!("and {} more", members.len() - listing.len()));
+        }
+        println!(
+            "Largest clique, {} elves all cleaning {common}:\n{}",
+            members.len(),
+            listing.join(", ")
+        );
+    }
 }
 
-pub fn part_b() {
-    let file_path = format!("src/input{DAY}.txt");
+/// Args: `[assignments] [csv]`, the file to analyse (default the puzzle input) and
+/// where to write the coverage profile, if anywhere
+pub fn part_sweep(args: &[String]) {
+    let file_path = args
+        .first()
+        .cloned()
+        .unwrap_or_else(|| format!("src/input{DAY}.txt"));
 
     let contents =
         fs::read_to_string(file_path).expect("Should have been able to read {DAY} the file");
 
+    let elves = elves(&contents);
+    let sections: Vec<Interval> = elves.iter().map(|e| e.sections).collect();
     let it = Input {
         lines: contents.lines(),
     };
+    let same_line = it
+        .map(|row| graph::relations(&row.ranges).len() as u64)
+        .sum::<u64>();
+    let pairs = sweep::overlapping_pairs(&sections);
+    let profile = sweep::profile(&sections);
 
-    let mut results: u32 = 0;
-    for row in it {
-        let overlap_start = cmp::max(row.e1_start, row.e2_start);
-        let overlap_end = cmp::min(row.e1_end, row.e2_end);
+    println!("Day {DAY} sweep over {} elves:", elves.len());
+    println!(
+        "Overlapping pairs: {pairs}, {} across lines",
+        pairs - same_line
+    );
+    if let Some(c) = sweep::deepest(&profile) {
+        println!(
+            "Most covered: section {} by {} elves",
+            c.sections.start, c.depth
+        );
+    }
+    if let Some(csv_path) = args.get(1) {
+        fs::write(csv_path, sweep::to_csv(&profile))
+            .expect("Should have been able to write the profile");
+        println!("Wrote {} runs to {csv_path}", profile.len());
+    }
+}
+
+fn listing(ranges: &[Interval]) -> String {
+    let ranges: Vec<String> = ranges.iter().map(Interval::to_string).collect();
+    ranges.join(",")
+}
 
-        if overlap_start <= overlap_end {
-            results += 1
+/// Args: `[min] [bounds] [assignments]`, sections each elf keeps (default 1), the
+/// sections allowed as `start-end` (default those already assigned) and the file to
+/// adjust (default the puzzle input)
+pub fn part_reassign(args: &[String]) {
+    let min_len: u32 = args
+        .first()
+        .map_or(1, |n| n.parse().expect("min should be a number"));
+    let file_path = args
+        .get(2)
+        .cloned()
+        .unwrap_or_else(|| format!("src/input{DAY}.txt"));
+
+    let contents =
+        fs::read_to_string(file_path).expect("Should have been able to read {DAY} the file");
+
+    let rows: Vec<InputRow> = Input {
+        lines: contents.lines(),
+    }
+    .collect();
+    let bounds = match args.get(1) {
+        Some(text) => {
+            let (start, end) = text.split_once('-').expect("bounds should be start-end");
+            Interval::new(
+                start.parse().expect("bounds should be numbers"),
+                end.parse().expect("bounds should be numbers"),
+            )
+        }
+        None => rows
+            .iter()
+            .flat_map(|row| row.ranges.iter().copied())
+            .reduce(|acc, r| acc.hull(&r))
+            .unwrap_or(Interval::new(1, 1)),
+    };
+    let constraints = reassign::Constraints { min_len, bounds };
+
+    let mut changed = 0;
+    let mut total = 0;
+    for (i, row) in rows.iter().enumerate() {
+        let (placed, cost) = reassign::reassign(&row.ranges, &constraints)
+            .unwrap_or_else(|e| panic!("line {}: {e}", i + 1));
+        if cost > 0 {
+            println!(
+                "Line {}: {} -> {} (cost {cost})",
+                i + 1,
+                listing(&row.ranges),
+                listing(&placed)
+            );
+            changed += 1;
+            total += cost;
         }
     }
-    // Solution 956
-    println!("Day {DAY}b best:\n{results}");
+    println!(
+        "Day {DAY} reassignment within {bounds}, at least {min_len} sections each:\n\
+         {changed} lines changed, total cost {total}"
+    );
 }
 
 #[cfg(test)]
@@ -113,4 +292,41 @@ mod tests {
     fn do_part_b() {
         part_b();
     }
+
+    #[test]
+    fn do_part_gaps() {
+        let contents = fs::read_to_string("src/input4s.txt").unwrap();
+        assert_eq!(uncovered(&contents).intervals(), &[Interval::new(1, 1)]);
+    }
+
+    #[test]
+    fn any_number_of_ranges() {
+        let contents = "1-3,2-2,3-5\n7-9\n4-4,8-8\n";
+        let elves = elves(contents);
+        assert_eq!(elves.len(), 6);
+        assert_eq!(elves[5].line, 3);
+        assert_eq!(elves[5].position, 2);
+
+        // 1-3 and 2-2 meet at section 2, 1-3 and 3-5 at 3, 3-5 and 4-4 at 4, 7-9 and
+        // 8-8 at 8
+        let graph = OverlapGraph::new(elves);
+        assert_eq!(graph.edge_count(), 4);
+        let (members, common) = graph.largest_clique().unwrap();
+        assert_eq!(members.len(), 2);
+        assert_eq!(common, Interval::new(2, 2));
+    }
+
+    #[test]
+    fn sweep_matches_graph() {
+        let contents = fs::read_to_string("src/input4s.txt").unwrap();
+        let elves = elves(&contents);
+        let sections: Vec<Interval> = elves.iter().map(|e| e.sections).collect();
+        let graph = OverlapGraph::new(elves);
+        assert_eq!(
+            sweep::overlapping_pairs(&sections),
+            graph.edge_count() as u64
+        );
+        let deepest = sweep::deepest(&sweep::profile(&sections)).unwrap();
+        assert_eq!(deepest.depth, graph.largest_clique().unwrap().0.len());
+    }
 }
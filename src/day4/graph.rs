@@ -0,0 +1,162 @@
+use std::fmt;
+
+use super::sweep;
+use crate::interval::Interval;
+
+/// One elf's assignment, found by 1 based line and position on the line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Elf {
+    pub line: usize,
+    pub position: usize,
+    pub sections: Interval,
+}
+
+impl fmt::Display for Elf {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{} ({})", self.line, self.position, self.sections)
+    }
+}
+
+/// How the first of two ranges relates to the second
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Relation {
+    Equal,
+    Contains,
+    Within,
+    Overlaps,
+}
+
+impl fmt::Display for Relation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = match self {
+            Relation::Equal => "equals",
+            Relation::Contains => "contains",
+            Relation::Within => "is within",
+            Relation::Overlaps => "overlaps",
+        };
+        write!(f, "{text}")
+    }
+}
+
+pub fn relation(a: &Interval, b: &Interval) -> Option<Relation> {
+    if a == b {
+        Some(Relation::Equal)
+    } else if a.contains(b) {
+        Some(Relation::Contains)
+    } else if b.contains(a) {
+        Some(Relation::Within)
+    } else if a.overlaps(b) {
+        Some(Relation::Overlaps)
+    } else {
+        None
+    }
+}
+
+/// Every pair of ranges on a line that share a section, by 0 based position
+pub fn relations(ranges: &[Interval]) -> Vec<(usize, usize, Relation)> {
+    let mut results = Vec::new();
+    for (i, a) in ranges.iter().enumerate() {
+        for (j, b) in ranges.iter().enumerate().skip(i + 1) {
+            if let Some(r) = relation(a, b) {
+                results.push((i, j, r));
+            }
+        }
+    }
+    results
+}
+
+/// Elves joined wherever their assignments share a section, whatever line they're on
+pub struct OverlapGraph {
+    pub elves: Vec<Elf>,
+    adjacent: Vec<Vec<usize>>,
+}
+
+impl OverlapGraph {
+    pub fn new(elves: Vec<Elf>) -> OverlapGraph {
+        let mut order: Vec<usize> = (0..elves.len()).collect();
+        order.sort_by_key(|&e| elves[e].sections.start);
+
+        // Sweep by start, every elf still active overlaps the next one
+        let mut adjacent = vec![Vec::new(); elves.len()];
+        let mut active: Vec<usize> = Vec::new();
+        for e in order {
+            let start = elves[e].sections.start;
+            active.retain(|&a| elves[a].sections.end >= start);
+            for &a in &active {
+                adjacent[a].push(e);
+                adjacent[e].push(a);
+            }
+            active.push(e);
+        }
+        for list in &mut adjacent {
+            list.sort_unstable();
+        }
+        OverlapGraph { elves, adjacent }
+    }
+
+    #[cfg(test)]
+    pub fn neighbours(&self, elf: usize) -> &[usize] {
+        &self.adjacent[elf]
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.adjacent.iter().map(Vec::len).sum::<usize>() / 2
+    }
+
+    /// Largest set of elves that all overlap each other, and the sections they share.
+    ///
+    /// Ranges that overlap pairwise always have a section in common, so this is just
+    /// the most covered section.
+    pub fn largest_clique(&self) -> Option<(Vec<usize>, Interval)> {
+        let sections: Vec<Interval> = self.elves.iter().map(|e| e.sections).collect();
+        let point = sweep::deepest(&sweep::profile(&sections))?.sections.start;
+
+        let members: Vec<usize> = (0..self.elves.len())
+            .filter(|&e| self.elves[e].sections.contains_point(point))
+            .collect();
+        let common = members
+            .iter()
+            .map(|&e| self.elves[e].sections)
+            .reduce(|acc, s| acc.intersection(&s).unwrap())?;
+        Some((members, common))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overlap_graph() {
+        let ranges = [(2, 4), (3, 9), (4, 4), (5, 6), (10, 12)];
+        let elves: Vec<Elf> = ranges
+            .iter()
+            .enumerate()
+            .map(|(i, &(s, e))| Elf {
+                line: 1,
+                position: i + 1,
+                sections: Interval::new(s, e),
+            })
+            .collect();
+
+        let sections: Vec<Interval> = elves.iter().map(|e| e.sections).collect();
+        assert_eq!(
+            relations(&sections),
+            vec![
+                (0, 1, Relation::Overlaps),
+                (0, 2, Relation::Contains),
+                (1, 2, Relation::Contains),
+                (1, 3, Relation::Contains),
+            ]
+        );
+
+        let graph = OverlapGraph::new(elves);
+        assert_eq!(graph.edge_count(), 4);
+        assert_eq!(graph.neighbours(1), &[0, 2, 3]);
+        assert_eq!(graph.neighbours(4), &[] as &[usize]);
+        assert_eq!(
+            graph.largest_clique(),
+            Some((vec![0, 1, 2], Interval::new(4, 4)))
+        );
+    }
+}
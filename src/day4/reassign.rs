@@ -0,0 +1,203 @@
+use std::collections::BinaryHeap;
+use std::fmt;
+
+use crate::interval::Interval;
+
+pub struct Constraints {
+    /// Fewest sections any elf may be left with
+    pub min_len: u32,
+    /// Every assignment has to stay inside these sections
+    pub bounds: Interval,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct NoRoom {
+    pub elves: usize,
+    pub min_len: u32,
+    pub bounds: Interval,
+}
+
+impl fmt::Display for NoRoom {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} elves with at least {} sections each don't fit in {}",
+            self.elves, self.min_len, self.bounds
+        )
+    }
+}
+
+/// Lines with more elves than this are only tried in order of their midpoints
+const MAX_PERMUTED: usize = 7;
+
+/// Every ordering of `0..k`
+fn permutations(k: usize) -> Vec<Vec<usize>> {
+    if k == 0 {
+        return vec![Vec::new()];
+    }
+    let mut results = Vec::new();
+    for shorter in permutations(k - 1) {
+        for i in 0..k {
+            let mut p = shorter.clone();
+            p.insert(i, k - 1);
+            results.push(p);
+        }
+    }
+    results
+}
+
+/// Nondecreasing `y` within `lo..=hi` closest to `targets`, by total distance.
+///
+/// The slope trick: keep the larger half of the targets seen so far in a max heap,
+/// then walk back down so no value is above the next. Clamping the unbounded answer
+/// into the box keeps it optimal.
+fn isotonic(targets: &[i64], lo: i64, hi: i64) -> Vec<i64> {
+    let mut heap = BinaryHeap::new();
+    let mut tops = Vec::with_capacity(targets.len());
+    for &t in targets {
+        heap.push(t);
+        if *heap.peek().unwrap() > t {
+            heap.pop();
+            heap.push(t);
+        }
+        tops.push(*heap.peek().unwrap());
+    }
+
+    for j in (0..tops.len().saturating_sub(1)).rev() {
+        tops[j] = tops[j].min(tops[j + 1]);
+    }
+    tops.iter().map(|&y| y.clamp(lo, hi)).collect()
+}
+
+/// Cheapest disjoint ranges placed in `order`, or `None` if they can't fit
+fn place(ranges: &[Interval], order: &[usize], c: &Constraints) -> Option<(Vec<Interval>, u64)> {
+    // Every start and end in order, each a fixed distance at least past the one
+    // before it. Taking off the running total of those distances leaves a
+    // nondecreasing sequence.
+    let min_len = c.min_len.max(1) as i64;
+    let mut targets = Vec::new();
+    let mut offsets = Vec::new();
+    let mut offset = 0;
+    for (i, &e) in order.iter().enumerate() {
+        if i > 0 {
+            offset += 1;
+        }
+        offsets.push(offset);
+        targets.push(ranges[e].start as i64 - offset);
+        offset += min_len - 1;
+        offsets.push(offset);
+        targets.push(ranges[e].end as i64 - offset);
+    }
+
+    let lo = c.bounds.start as i64;
+    let hi = c.bounds.end as i64 - offset;
+    if lo > hi {
+        return None;
+    }
+    let ends: Vec<i64> = isotonic(&targets, lo, hi)
+        .iter()
+        .zip(&offsets)
+        .map(|(y, d)| y + d)
+        .collect();
+
+    let mut results = ranges.to_vec();
+    let mut cost = 0;
+    for (i, &e) in order.iter().enumerate() {
+        let placed = Interval::new(ends[2 * i] as u32, ends[2 * i + 1] as u32);
+        cost += ranges[e].start.abs_diff(placed.start) as u64;
+        cost += ranges[e].end.abs_diff(placed.end) as u64;
+        results[e] = placed;
+    }
+    Some((results, cost))
+}
+
+/// Shrink or shift ranges so no two share a section, moving their ends as little as
+/// possible in total. Returns the new ranges, in the same order, and that total.
+pub fn reassign(ranges: &[Interval], c: &Constraints) -> Result<(Vec<Interval>, u64), NoRoom> {
+    let orders = if ranges.len() <= MAX_PERMUTED {
+        permutations(ranges.len())
+    } else {
+        let mut order: Vec<usize> = (0..ranges.len()).collect();
+        order.sort_by_key(|&e| ranges[e].start as u64 + ranges[e].end as u64);
+        vec![order]
+    };
+
+    let mut best: Option<(Vec<Interval>, u64)> = None;
+    for order in orders {
+        if let Some((placed, cost)) = place(ranges, &order, c) {
+            if best.as_ref().is_none_or(|(_, b)| cost < *b) {
+                best = Some((placed, cost));
+            }
+        }
+    }
+    best.ok_or(NoRoom {
+        elves: ranges.len(),
+        min_len: c.min_len,
+        bounds: c.bounds,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cheapest pair by trying every pair of ranges in the bounds
+    fn brute_force(a: Interval, b: Interval, c: &Constraints) -> Option<u64> {
+        let (lo, hi) = (c.bounds.start, c.bounds.end);
+        let all: Vec<Interval> = (lo..=hi)
+            .flat_map(|s| (s..=hi).map(move |e| Interval::new(s, e)))
+            .filter(|r| r.len() >= c.min_len as u64)
+            .collect();
+        let moved = |from: Interval, to: &Interval| {
+            (from.start.abs_diff(to.start) + from.end.abs_diff(to.end)) as u64
+        };
+        all.iter()
+            .flat_map(|x| all.iter().map(move |y| (x, y)))
+            .filter(|(x, y)| !x.overlaps(y))
+            .map(|(x, y)| moved(a, x) + moved(b, y))
+            .min()
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        let pairs = [
+            (2, 4, 6, 8),
+            (5, 7, 7, 9),
+            (2, 8, 3, 7),
+            (6, 6, 4, 6),
+            (1, 9, 1, 9),
+        ];
+        for min_len in 1..=4 {
+            let c = Constraints {
+                min_len,
+                bounds: Interval::new(1, 9),
+            };
+            for &(a, b, x, y) in &pairs {
+                let (a, b) = (Interval::new(a, b), Interval::new(x, y));
+                let result = reassign(&[a, b], &c).ok();
+                if let Some((placed, _)) = &result {
+                    assert!(!placed[0].overlaps(&placed[1]));
+                    assert!(placed.iter().all(|p| p.len() >= min_len as u64));
+                }
+                assert_eq!(result.map(|(_, cost)| cost), brute_force(a, b, &c));
+            }
+        }
+    }
+
+    #[test]
+    fn no_room() {
+        let c = Constraints {
+            min_len: 3,
+            bounds: Interval::new(1, 5),
+        };
+        let ranges = [Interval::new(1, 3), Interval::new(2, 4)];
+        assert_eq!(
+            reassign(&ranges, &c),
+            Err(NoRoom {
+                elves: 2,
+                min_len: 3,
+                bounds: Interval::new(1, 5)
+            })
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use crate::interval::Interval;
+
+/// A run of sections all covered by the same number of elves
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Coverage {
+    pub sections: Interval,
+    pub depth: usize,
+}
+
+/// Coverage depth from the first assigned section to the last, in order
+pub fn profile(ranges: &[Interval]) -> Vec<Coverage> {
+    // Depth changes just before a start and just after an end
+    let mut events: Vec<(u64, isize)> = ranges
+        .iter()
+        .flat_map(|r| [(r.start as u64, 1), (r.end as u64 + 1, -1)])
+        .collect();
+    events.sort_unstable();
+
+    let mut results = Vec::new();
+    let mut depth: isize = 0;
+    let mut i = 0;
+    while i < events.len() {
+        let point = events[i].0;
+        while i < events.len() && events[i].0 == point {
+            depth += events[i].1;
+            i += 1;
+        }
+        if let Some(&(next, _)) = events.get(i) {
+            results.push(Coverage {
+                sections: Interval::new(point as u32, (next - 1) as u32),
+                depth: depth as usize,
+            });
+        }
+    }
+    results
+}
+
+/// The first of the most covered runs
+pub fn deepest(profile: &[Coverage]) -> Option<Coverage> {
+    profile
+        .iter()
+        .copied()
+        .reduce(|best, c| if c.depth > best.depth { c } else { best })
+}
+
+/// Number of pairs of ranges sharing at least one section.
+///
+/// Every pair either overlaps or has one range entirely after the other, and the
+/// ranges after each one are counted with a binary search of the sorted starts.
+pub fn overlapping_pairs(ranges: &[Interval]) -> u64 {
+    let mut starts: Vec<u32> = ranges.iter().map(|r| r.start).collect();
+    starts.sort_unstable();
+
+    let n = ranges.len() as u64;
+    let disjoint: u64 = ranges
+        .iter()
+        .map(|r| (starts.len() - starts.partition_point(|&s| s <= r.end)) as u64)
+        .sum();
+    n * n.saturating_sub(1) / 2 - disjoint
+}
+
+pub fn to_csv(profile: &[Coverage]) -> String {
+    let mut results = String::from("start,end,depth\n");
+    for c in profile {
+        results += &format!("{},{},{}\n", c.sections.start, c.sections.end, c.depth);
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sweeps_ranges() {
+        let ranges = [
+            Interval::new(2, 4),
+            Interval::new(3, 9),
+            Interval::new(4, 4),
+            Interval::new(5, 6),
+            Interval::new(11, 12),
+        ];
+        let profile = profile(&ranges);
+        assert_eq!(
+            to_csv(&profile),
+            "start,end,depth\n2,2,1\n3,3,2\n4,4,3\n5,6,2\n7,9,1\n10,10,0\n11,12,1\n"
+        );
+        assert_eq!(
+            deepest(&profile),
+            Some(Coverage {
+                sections: Interval::new(4, 4),
+                depth: 3
+            })
+        );
+        assert_eq!(overlapping_pairs(&ranges), 4);
+        assert_eq!(overlapping_pairs(&[]), 0);
+    }
+}
@@ -1,5 +1,10 @@
+use std::fmt;
 use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::ops::{Index, IndexMut};
 use std::str;
+use std::thread;
+use std::time::Duration;
 
 const DAY: i32 = 5;
 
@@ -7,145 +12,600 @@ fn file_path() -> String {
     format!("src/input{DAY}.txt")
 }
 
-type Stack = Vec<char>;
-type Stacks = Vec<Stack>;
+type Stack = Vec<String>;
+
+/// Stacks of crates, bottom first
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Stacks(Vec<Stack>);
+
+impl Stacks {
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn iter(&self) -> std::slice::Iter<'_, Stack> {
+        self.0.iter()
+    }
+
+    /// The drawing, marking the top `count` crates of the 0 based stack in `moved`. Marked
+    /// crates are in reverse video with `ansi`, otherwise in angle brackets.
+    fn render(&self, moved: Option<(usize, usize)>, ansi: bool) -> String {
+        // Every column is wide enough for the longest crate and stack number
+        let width = self
+            .iter()
+            .flatten()
+            .map(|c| c.chars().count())
+            .chain([self.len().to_string().len()])
+            .max()
+            .unwrap_or(1)
+            + 2;
+        let pad = |text: String, len: usize| {
+            let left = (width - len) / 2;
+            format!(
+                "{}{text}{}",
+                " ".repeat(left),
+                " ".repeat(width - len - left)
+            )
+        };
+
+        let height = self.iter().map(Vec::len).max().unwrap_or(0);
+        let mut lines = Vec::new();
+        for level in (0..height).rev() {
+            let row: Vec<String> = self
+                .iter()
+                .enumerate()
+                .map(|(i, s)| {
+                    let marked =
+                        moved.is_some_and(|(stack, count)| stack == i && level + count >= s.len());
+                    match s.get(level) {
+                        Some(c) => {
+                            let cell = match (marked, ansi) {
+                                (true, true) => format!("\x1b[7m[{c}]\x1b[0m"),
+                                (true, false) => format!("<{c}>"),
+                                (false, _) => format!("[{c}]"),
+                            };
+                            pad(cell, c.chars().count() + 2)
+                        }
+                        None => " ".repeat(width),
+                    }
+                })
+                .collect();
+            lines.push(row.join(" "));
+        }
+        let index: Vec<String> = (1..=self.len())
+            .map(|i| pad(i.to_string(), i.to_string().len()))
+            .collect();
+        lines.push(index.join(" "));
+        lines.join("\n")
+    }
+
+    /// Each stack on one line, bottom first, like `1:ZN 2:MCD 3:P`. Crates with longer
+    /// names are separated by commas.
+    fn summary(&self) -> String {
+        let stacks: Vec<String> = self
+            .iter()
+            .enumerate()
+            .map(|(i, s)| {
+                let separator = if s.iter().all(|c| c.chars().count() == 1) {
+                    ""
+                } else {
+                    ","
+                };
+                format!("{}:{}", i + 1, s.join(separator))
+            })
+            .collect();
+        stacks.join(" ")
+    }
+
+    /// The crate on top of each stack, skipping empty ones
+    fn tops(&self) -> String {
+        self.iter()
+            .filter_map(|s| s.last())
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+impl Index<usize> for Stacks {
+    type Output = Stack;
+
+    fn index(&self, index: usize) -> &Stack {
+        &self.0[index]
+    }
+}
+
+impl IndexMut<usize> for Stacks {
+    fn index_mut(&mut self, index: usize) -> &mut Stack {
+        &mut self.0[index]
+    }
+}
+
+/// The drawing from the puzzle, tallest stack first and the index row last
+impl fmt::Display for Stacks {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.render(None, false))
+    }
+}
 
 #[derive(Debug)]
 struct Command {
-    count: u8,
+    count: usize,
     from: usize,
     to: usize,
+    /// 1 based line number in the input
+    line: usize,
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "move {} from {} to {}", self.count, self.from, self.to)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum ProcedureError {
+    BadDrawing {
+        line: usize,
+        reason: String,
+    },
+    /// A line that isn't `move N from A to B`
+    Malformed {
+        line: usize,
+        text: String,
+    },
+    NoSuchStack {
+        line: usize,
+        command: String,
+        stack: usize,
+        state: String,
+    },
+    TooFewCrates {
+        line: usize,
+        command: String,
+        present: usize,
+        state: String,
+    },
+}
+
+impl fmt::Display for ProcedureError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProcedureError::BadDrawing { line, reason } => write!(f, "line {line}: {reason}"),
+            ProcedureError::Malformed { line, text } => {
+                write!(f, "line {line}: {text:?} is not a move")
+            }
+            ProcedureError::NoSuchStack {
+                line,
+                command,
+                stack,
+                state,
+            } => write!(
+                f,
+                "line {line}: {command}: there is no stack {stack}, stacks are {state}"
+            ),
+            ProcedureError::TooFewCrates {
+                line,
+                command,
+                present,
+                state,
+            } => write!(
+                f,
+                "line {line}: {command}: only {present} crates to move, stacks are {state}"
+            ),
+        }
+    }
+}
+
+impl ProcedureError {
+    fn line(&self) -> usize {
+        match self {
+            ProcedureError::BadDrawing { line, .. }
+            | ProcedureError::Malformed { line, .. }
+            | ProcedureError::NoSuchStack { line, .. }
+            | ProcedureError::TooFewCrates { line, .. } => *line,
+        }
+    }
+}
+
+/// One error per line
+fn report(errors: &[ProcedureError]) -> String {
+    let lines: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+    lines.join("\n")
 }
 
 struct Input<'a> {
     lines: str::Lines<'a>,
+    // 1 based number of the last line read
+    line: usize,
 }
 
 impl<'a> Input<'a> {
-    fn stacks(&mut self) -> Stacks {
-        let mut first_line = true;
-        let mut results = Stacks::new();
-
-        for line in &mut self.lines {
-            // Blank row before moves input
-            if line.is_empty() {
-                // results are all backwards
-                results.iter_mut().for_each(|r| r.reverse());
-
-                println! {"{results:?}"};
-                return results;
-            }
-            // indexing row
-            if line.starts_with(" 1 ") {
+    fn new(contents: &'a str) -> Input<'a> {
+        Input {
+            lines: contents.lines(),
+            line: 0,
+        }
+    }
+
+    /// Read the drawing down to the index row, which says which column each stack is in
+    fn stacks(&mut self) -> Result<Stacks, ProcedureError> {
+        let mut rows = Vec::new();
+        for text in self.lines.by_ref() {
+            self.line += 1;
+            let Some(labels) = index_row(text) else {
+                rows.push((self.line, text));
                 continue;
+            };
+            let index_line = self.line;
+            let bad = |line, reason: String| ProcedureError::BadDrawing { line, reason };
+
+            if labels.iter().enumerate().any(|(i, l)| l.label != i + 1) {
+                return Err(bad(
+                    index_line,
+                    String::from("stacks should be numbered from 1 in order"),
+                ));
             }
+            if self.lines.next().is_some_and(|t| !t.trim().is_empty()) {
+                return Err(bad(
+                    index_line + 1,
+                    String::from("expected a blank line after the index row"),
+                ));
+            }
+            self.line += 1;
+
+            // Bottom row first, so every crate has to land on the one under it
+            let mut results = vec![Stack::new(); labels.len()];
+            for (depth, &(line, text)) in rows.iter().rev().enumerate() {
+                for token in crate_tokens(text).map_err(|reason| bad(line, reason))? {
+                    let stack = labels
+                        .iter()
+                        .enumerate()
+                        .map(|(i, l)| (token.overlap(l), i))
+                        .max()
+                        .filter(|&(overlap, _)| overlap > 0)
+                        .map(|(_, i)| i)
+                        .ok_or_else(|| {
+                            bad(
+                                line,
+                                format!(
+                                    "crate [{}] at column {} is not above a stack",
+                                    token.text,
+                                    token.start + 1
+                                ),
+                            )
+                        })?;
+                    if results[stack].len() != depth {
+                        return Err(bad(
+                            line,
+                            format!("crate [{}] has nothing under it", token.text),
+                        ));
+                    }
+                    results[stack].push(token.text);
+                }
+            }
+            return Ok(Stacks(results));
+        }
+        Err(ProcedureError::BadDrawing {
+            line: self.line,
+            reason: String::from("no index row under the drawing"),
+        })
+    }
+}
+
+/// A run of text in a drawing row, by 0 based character columns
+struct Token {
+    start: usize,
+    end: usize,
+    text: String,
+}
+
+impl Token {
+    /// Number of columns shared with `other`
+    fn overlap(&self, other: &Label) -> usize {
+        let start = self.start.max(other.start);
+        let end = self.end.min(other.end);
+        (end + 1).saturating_sub(start)
+    }
+}
+
+/// A stack number in the index row, by 0 based character columns
+struct Label {
+    start: usize,
+    end: usize,
+    label: usize,
+}
 
-            let result_size = (line.len() + 1) / 4;
-            let mut chars = line.chars();
-
-            let mut boxes = Vec::new();
-            for _ in 0..result_size {
-                // 1..41..4
-                // [0] [2]
-                // 0123456
-                //  1...5
-                // entries are ~4 characters wide
-                // we want the 2nd character, so offset of 1
-
-                // This would be so much easier if we could iterate over iterators
-                // skip one
-                chars.next();
-                let val = chars.next().unwrap();
-                boxes.push(val);
-                // skip two more
-                chars.next();
-                chars.next();
+/// Every whitespace separated word of `text` with its columns
+fn words(text: &str) -> Vec<(usize, usize, &str)> {
+    let mut results = Vec::new();
+    let mut start = None;
+    let mut column = 0;
+    for (i, c) in text.char_indices() {
+        match (c.is_whitespace(), start) {
+            (false, None) => start = Some((i, column)),
+            (true, Some((byte, first))) => {
+                results.push((first, column - 1, &text[byte..i]));
+                start = None;
             }
+            _ => (),
+        }
+        column += 1;
+    }
+    if let Some((byte, first)) = start {
+        results.push((first, column - 1, &text[byte..]));
+    }
+    results
+}
 
-            // Size out the results on the first real row
-            if first_line {
-                first_line = false;
+/// The stack numbers, if `text` is made of nothing else
+fn index_row(text: &str) -> Option<Vec<Label>> {
+    let labels: Option<Vec<Label>> = words(text)
+        .into_iter()
+        .map(|(start, end, word)| {
+            Some(Label {
+                start,
+                end,
+                label: word.parse().ok()?,
+            })
+        })
+        .collect();
+    labels.filter(|l| !l.is_empty())
+}
 
-                // Add one to length for missing separator after final stack
-                for _ in 0..result_size {
-                    results.push(Stack::new());
+/// The bracketed crates in a drawing row, where crates may have names of any length
+fn crate_tokens(text: &str) -> Result<Vec<Token>, String> {
+    let mut results = Vec::new();
+    let mut chars = text.chars().enumerate();
+    while let Some((column, c)) = chars.next() {
+        match c {
+            '[' => {
+                let mut name = String::new();
+                let end = loop {
+                    match chars.next() {
+                        Some((end, ']')) => break end,
+                        Some((_, c)) => name.push(c),
+                        None => {
+                            return Err(format!("crate at column {} is not closed", column + 1))
+                        }
+                    }
+                };
+                if name.is_empty() {
+                    return Err(format!("crate at column {} has no name", column + 1));
                 }
+                results.push(Token {
+                    start: column,
+                    end,
+                    text: name,
+                });
             }
-            // True row of data
-            for (i, &v) in boxes.iter().enumerate() {
-                if v != ' ' {
-                    results[i].push(v);
-                }
+            c if c.is_whitespace() => (),
+            c => {
+                return Err(format!(
+                    "{c:?} at column {} is not part of a crate",
+                    column + 1
+                ))
             }
         }
-        panic!("not enough input");
     }
+    Ok(results)
 }
 
 impl<'a> Iterator for Input<'a> {
-    type Item = Command;
+    type Item = Result<Command, ProcedureError>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let line = self.lines.next();
-        match line {
-            Some(text) => {
-                if text.is_empty() {
-                    return None;
+        for text in self.lines.by_ref() {
+            self.line += 1;
+            // Blank lines between moves don't end the procedure
+            if text.trim().is_empty() {
+                continue;
+            }
+
+            let words: Vec<&str> = text.split_whitespace().collect();
+            let command = match words[..] {
+                ["move", count, "from", from, "to", to] => {
+                    match (count.parse(), from.parse(), to.parse()) {
+                        (Ok(count), Ok(from), Ok(to)) => Some(Command {
+                            count,
+                            from,
+                            to,
+                            line: self.line,
+                        }),
+                        _ => None,
+                    }
                 }
+                _ => None,
+            };
+            return Some(command.ok_or_else(|| ProcedureError::Malformed {
+                line: self.line,
+                text: text.to_string(),
+            }));
+        }
+        None
+    }
+}
 
-                let values: Vec<u8> = text
-                    .split_whitespace()
-                    .filter_map(|section| section.parse::<u8>().ok())
-                    .collect();
+/// The starting stacks, every command and every line that isn't a command
+fn parse(contents: &str) -> Result<(Stacks, Vec<Command>, Vec<ProcedureError>), ProcedureError> {
+    let mut it = Input::new(contents);
+    let stacks = it.stacks()?;
 
-                Some(Command {
-                    count: values[0],
-                    from: values[1] as usize,
-                    to: values[2] as usize,
-                })
-            }
-            None => None,
+    let mut commands = Vec::new();
+    let mut errors = Vec::new();
+    for cmd in it {
+        match cmd {
+            Ok(cmd) => commands.push(cmd),
+            Err(e) => errors.push(e),
         }
     }
+    Ok((stacks, commands, errors))
 }
 
-struct Input2<'a> {
-    lines: str::Lines<'a>,
+/// The starting stacks and commands, or every problem with them in line order when
+/// `crane` carries them out
+fn check(contents: &str, crane: &dyn Crane) -> Result<(Stacks, Vec<Command>), Vec<ProcedureError>> {
+    let (stacks, commands, mut errors) = parse(contents).map_err(|e| vec![e])?;
+    if let Err(e) = validate(&stacks, &commands, crane) {
+        errors.extend(e);
+    }
+    if errors.is_empty() {
+        Ok((stacks, commands))
+    } else {
+        errors.sort_by_key(ProcedureError::line);
+        Err(errors)
+    }
 }
-impl<'a> Iterator for Input2<'a> {
-    type Item = (&'a str, &'a str, &'a str);
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let line = self.lines.next();
-        match line {
-            Some(text) => Some((text, self.lines.next().unwrap(), self.lines.next().unwrap())),
-            None => None,
+/// Run through the procedure with `crane`, reporting every command that can't be carried
+/// out along with the stacks as they were. Bad commands are skipped so later ones are
+/// still checked.
+fn validate(
+    stacks: &Stacks,
+    commands: &[Command],
+    crane: &dyn Crane,
+) -> Result<(), Vec<ProcedureError>> {
+    let mut stacks = stacks.clone();
+    let mut errors = Vec::new();
+    for cmd in commands {
+        let mut missing: Vec<usize> = [cmd.from, cmd.to]
+            .into_iter()
+            .filter(|&s| s == 0 || s > stacks.len())
+            .collect();
+        missing.dedup();
+        if !missing.is_empty() {
+            for stack in missing {
+                errors.push(ProcedureError::NoSuchStack {
+                    line: cmd.line,
+                    command: cmd.to_string(),
+                    stack,
+                    state: stacks.summary(),
+                });
+            }
+            continue;
+        }
+        let present = stacks[cmd.from - 1].len();
+        if present < cmd.count {
+            errors.push(ProcedureError::TooFewCrates {
+                line: cmd.line,
+                command: cmd.to_string(),
+                present,
+                state: stacks.summary(),
+            });
+            continue;
         }
+        crane.apply(&mut stacks, cmd);
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
     }
 }
 
-pub fn part_a() {
-    let contents =
-        fs::read_to_string(file_path()).expect("Should have been able to read {DAY} the file");
+/// Something that can carry out a rearrangement procedure
+trait Crane {
+    fn name(&self) -> String;
+
+    /// Move `cmd.count` crates between stacks, indexed from 1
+    fn apply(&self, stacks: &mut Stacks, cmd: &Command);
+}
 
-    let mut it = Input {
-        lines: contents.lines(),
-    };
+/// Lift the top `count` crates off a stack, bottom first
+fn lift(stacks: &mut Stacks, from: usize, count: usize) -> Stack {
+    let stack = &mut stacks[from - 1];
+    stack.split_off(stack.len() - count)
+}
 
-    let mut stacks = it.stacks();
+/// Moves crates one at a time, so a block lands reversed
+struct CrateMover9000;
 
-    println!("{stacks:?}");
-    // let mut results: Vec<char> = Vec::new();
-    for cmd in it {
-        println!("{cmd:?}");
-        // move `count` from `from` to `to`
-        for _ in 0..cmd.count {
-            let c = stacks[cmd.from - 1].pop().unwrap();
-            stacks[cmd.to - 1].push(c);
+impl Crane for CrateMover9000 {
+    fn name(&self) -> String {
+        String::from("CrateMover 9000")
+    }
+
+    fn apply(&self, stacks: &mut Stacks, cmd: &Command) {
+        let block = lift(stacks, cmd.from, cmd.count);
+        stacks[cmd.to - 1].extend(block.into_iter().rev());
+    }
+}
+
+/// Moves a whole block at once, keeping its order
+struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn name(&self) -> String {
+        String::from("CrateMover 9001")
+    }
+
+    fn apply(&self, stacks: &mut Stacks, cmd: &Command) {
+        let block = lift(stacks, cmd.from, cmd.count);
+        stacks[cmd.to - 1].extend(block);
+    }
+}
+
+/// Moves blocks like a 9001, but no more than `capacity` crates per lift
+struct LimitedCrane {
+    capacity: usize,
+}
+
+impl Crane for LimitedCrane {
+    fn name(&self) -> String {
+        format!("crane lifting {} at a time", self.capacity)
+    }
+
+    fn apply(&self, stacks: &mut Stacks, cmd: &Command) {
+        let mut remaining = cmd.count;
+        while remaining > 0 {
+            let count = remaining.min(self.capacity);
+            let block = lift(stacks, cmd.from, count);
+            stacks[cmd.to - 1].extend(block);
+            remaining -= count;
         }
     }
+}
+
+/// Carry out the procedure in `contents` with `crane`, returning the top crates. Nothing
+/// is moved unless every command is valid.
+///
+/// `watch` sees the stacks before the first move, with no command, then after every
+/// move along with how many moves have been made.
+fn simulate_watched<F>(
+    contents: &str,
+    crane: &dyn Crane,
+    mut watch: F,
+) -> Result<String, Vec<ProcedureError>>
+where
+    F: FnMut(usize, Option<&Command>, &Stacks),
+{
+    let (mut stacks, commands) = check(contents, crane)?;
+
+    watch(0, None, &stacks);
+    for (i, cmd) in commands.iter().enumerate() {
+        crane.apply(&mut stacks, cmd);
+        watch(i + 1, Some(cmd), &stacks);
+    }
+    Ok(stacks.tops())
+}
 
-    let results: String = stacks.iter().filter_map(|s| s.last()).collect();
+fn simulate(contents: &str, crane: &dyn Crane) -> Result<String, Vec<ProcedureError>> {
+    simulate_watched(contents, crane, |_, _, _| {})
+}
+
+/// A CrateMover by model number
+fn crane(model: &str) -> Box<dyn Crane> {
+    match model {
+        "9000" => Box::new(CrateMover9000),
+        "9001" => Box::new(CrateMover9001),
+        _ => panic!("crane should be 9000 or 9001"),
+    }
+}
+
+pub fn part_a() {
+    let contents =
+        fs::read_to_string(file_path()).expect("Should have been able to read {DAY} the file");
+
+    let results = simulate(&contents, &CrateMover9000).unwrap_or_else(|e| panic!("{}", report(&e)));
     // Solution BWNCQRMDB
     println!("Day {DAY}a best:\n{results}");
 }
@@ -154,32 +614,122 @@ pub fn part_b() {
     let contents =
         fs::read_to_string(file_path()).expect("Should have been able to read {DAY} the file");
 
-    let mut it = Input {
-        lines: contents.lines(),
-    };
+    let results = simulate(&contents, &CrateMover9001).unwrap_or_else(|e| panic!("{}", report(&e)));
+    // Solution NHWZCBNBF
+    println!("Day {DAY}b best:\n{results}");
+}
 
-    let mut stacks = it.stacks();
+/// Args: `[capacity]`, the most crates the crane lifts at once (default 3)
+pub fn part_crane(args: &[String]) {
+    let capacity: usize = args
+        .first()
+        .map_or(3, |n| n.parse().expect("capacity should be a number"));
+    assert!(capacity > 0, "capacity should be at least 1");
 
-    println!("{stacks:?}");
-    for cmd in it {
-        println!("{cmd:?}");
-        // move `count` from `from` to `to`
-        // Push and pop onto a temporary stack to preserve order
-        let mut tmp: Vec<char> = Vec::new();
+    let contents =
+        fs::read_to_string(file_path()).expect("Should have been able to read {DAY} the file");
+
+    let crane = LimitedCrane { capacity };
+    let results = simulate(&contents, &crane).unwrap_or_else(|e| panic!("{}", report(&e)));
+    println!("Day {DAY} {}:\n{results}", crane.name());
+}
+
+/// Args: `[procedure] [crane]`, the file to check (default the puzzle input) and the
+/// CrateMover model as for `part_show`
+pub fn part_validate(args: &[String]) {
+    let file_path = args.first().cloned().unwrap_or_else(file_path);
+    let crane = crane(args.get(1).map_or("9000", |c| c.as_str()));
+
+    let contents =
+        fs::read_to_string(file_path).expect("Should have been able to read {DAY} the file");
+
+    match check(&contents, crane.as_ref()) {
+        Ok((_, commands)) => println!(
+            "Day {DAY} procedure is valid for the {}:\n{} moves",
+            crane.name(),
+            commands.len()
+        ),
+        Err(errors) => println!(
+            "Day {DAY} procedure has {} problems for the {}:\n{}",
+            errors.len(),
+            crane.name(),
+            report(&errors)
+        ),
+    }
+}
+
+/// Args: `[every] [crane]`, moves between drawings (default 1) and the CrateMover
+/// model, `9000` or `9001` (default 9000)
+pub fn part_show(args: &[String]) {
+    let every: usize = args
+        .first()
+        .map_or(1, |n| n.parse().expect("every should be a number"));
+    assert!(every > 0, "every should be at least 1");
+    let crane = crane(args.get(1).map_or("9000", |c| c.as_str()));
+
+    let contents =
+        fs::read_to_string(file_path()).expect("Should have been able to read {DAY} the file");
+
+    let results = simulate_watched(&contents, crane.as_ref(), |moves, cmd, stacks| {
+        if moves.is_multiple_of(every) {
+            match cmd {
+                Some(cmd) => println!("After move {moves}, {cmd}:"),
+                None => println!("Start:"),
+            }
+            println!("{stacks}\n");
+        }
+    })
+    .unwrap_or_else(|e| panic!("{}", report(&e)));
+    println!("Day {DAY} {}:\n{results}", crane.name());
+}
+
+/// Args: `[delay_ms] [crane] [frames]`, the pause between frames (default 50), the
+/// CrateMover model as for `part_show`, and a file to write frames to instead of
+/// redrawing the terminal. When stdout isn't a terminal the frames are printed one
+/// after another.
+pub fn part_animate(args: &[String]) {
+    let delay: u64 = args
+        .first()
+        .map_or(50, |n| n.parse().expect("delay should be a number"));
+    let crane = crane(args.get(1).map_or("9000", |c| c.as_str()));
+    let frames_path = args.get(2);
+    let redraw = frames_path.is_none() && io::stdout().is_terminal();
 
-        for _ in 0..cmd.count {
-            let c = stacks[cmd.from - 1].pop().unwrap();
-            tmp.push(c);
+    let contents =
+        fs::read_to_string(file_path()).expect("Should have been able to read {DAY} the file");
+    let total = contents
+        .lines()
+        .skip_while(|line| !line.trim().is_empty())
+        .filter(|line| !line.trim().is_empty())
+        .count();
+
+    let mut frames = String::new();
+    let results = simulate_watched(&contents, crane.as_ref(), |moves, cmd, stacks| {
+        let progress = match cmd {
+            Some(cmd) => format!("Move {moves}/{total}: {cmd}"),
+            None => format!("Start, {total} moves"),
+        };
+        let moved = cmd.map(|cmd| (cmd.to - 1, cmd.count));
+        if redraw {
+            // Clear the screen and home the cursor
+            print!("\x1b[2J\x1b[H{progress}\n{}\n", stacks.render(moved, true));
+            io::stdout().flush().unwrap();
+            thread::sleep(Duration::from_millis(delay));
+        } else {
+            frames += &format!("{progress}\n{}\n\n", stacks.render(moved, false));
         }
-        for _ in 0..cmd.count {
-            let c = tmp.pop().unwrap();
-            stacks[cmd.to - 1].push(c);
+    })
+    .unwrap_or_else(|e| panic!("{}", report(&e)));
+
+    match frames_path {
+        Some(path) => {
+            fs::write(path, frames).expect("Should have been able to write the frames");
+            println!("Wrote {} frames to {path}", total + 1);
         }
+        None if !redraw => print!("{frames}"),
+        None => (),
     }
-
-    let results: String = stacks.iter().filter_map(|s| s.last()).collect();
-    // Solution NHWZCBNBF
-    println!("Day {DAY}b best:\n{results}");
+    println!("Day {DAY} {}:\n{results}", crane.name());
 }
 
 #[cfg(test)]
@@ -195,4 +745,195 @@ mod tests {
     fn do_part_b() {
         part_b();
     }
+
+    #[test]
+    fn cranes() {
+        let contents = fs::read_to_string("src/input5s.txt").unwrap();
+        assert_eq!(
+            simulate(&contents, &CrateMover9000),
+            Ok(String::from("CMZ"))
+        );
+        assert_eq!(
+            simulate(&contents, &CrateMover9001),
+            Ok(String::from("MCD"))
+        );
+        // One crate a time is a 9000, a big enough lift is a 9001
+        assert_eq!(
+            simulate(&contents, &LimitedCrane { capacity: 1 }).unwrap(),
+            "CMZ"
+        );
+        assert_eq!(
+            simulate(&contents, &LimitedCrane { capacity: 3 }).unwrap(),
+            "MCD"
+        );
+        // Moving 3 as 2 then 1 leaves Z on top of the N and D block
+        assert_eq!(
+            simulate(&contents, &LimitedCrane { capacity: 2 }).unwrap(),
+            "MCZ"
+        );
+    }
+
+    #[test]
+    fn renders_stacks() {
+        let contents = fs::read_to_string("src/input5s.txt").unwrap();
+        let drawing = contents.split("\n\n").next().unwrap();
+        let stacks = Input::new(&contents).stacks().unwrap();
+        assert_eq!(stacks.to_string(), drawing);
+
+        // Render the end state and read it back
+        let (mut stacks, commands) = check(&contents, &CrateMover9000).unwrap();
+        for cmd in &commands {
+            CrateMover9000.apply(&mut stacks, cmd);
+        }
+        let rendered = format!("{stacks}\n\n");
+        let parsed = Input::new(&rendered).stacks().unwrap();
+        assert_eq!(parsed, stacks);
+
+        // The crates just moved onto stack 1 are marked
+        assert_eq!(
+            stacks.render(Some((0, 1)), false),
+            "        [Z]\n        [N]\n        [D]\n<C> [M] [P]\n 1   2   3 "
+        );
+    }
+
+    #[test]
+    fn reports_bad_procedures() {
+        let contents = fs::read_to_string("src/input5s.txt").unwrap();
+        let drawing = contents.split("\n\n").next().unwrap();
+
+        let bad = format!("{drawing}\n\nmove two from 1 to 3\nmove 3 from 1 to 3\n");
+        assert_eq!(
+            simulate(&bad, &CrateMover9000),
+            Err(vec![
+                ProcedureError::Malformed {
+                    line: 6,
+                    text: String::from("move two from 1 to 3")
+                },
+                ProcedureError::TooFewCrates {
+                    line: 7,
+                    command: String::from("move 3 from 1 to 3"),
+                    present: 2,
+                    state: String::from("1:ZN 2:MCD 3:P"),
+                },
+            ])
+        );
+
+        let bad = format!(
+            "{drawing}\n\nmove 1 from 2 to 0\nmove 4 from 1 to 3\nmove 1 from 3 to 4\nmove 3 from 2 to 1\n"
+        );
+        assert_eq!(
+            simulate(&bad, &CrateMover9000),
+            Err(vec![
+                ProcedureError::NoSuchStack {
+                    line: 6,
+                    command: String::from("move 1 from 2 to 0"),
+                    stack: 0,
+                    state: String::from("1:ZN 2:MCD 3:P"),
+                },
+                ProcedureError::TooFewCrates {
+                    line: 7,
+                    command: String::from("move 4 from 1 to 3"),
+                    present: 2,
+                    state: String::from("1:ZN 2:MCD 3:P"),
+                },
+                ProcedureError::NoSuchStack {
+                    line: 8,
+                    command: String::from("move 1 from 3 to 4"),
+                    stack: 4,
+                    state: String::from("1:ZN 2:MCD 3:P"),
+                },
+            ])
+        );
+
+        // The stacks reported are the ones the crane in use would have built
+        let bad = format!("{drawing}\n\nmove 3 from 2 to 1\nmove 9 from 1 to 3\n");
+        let too_few = |state: &str| {
+            Err(vec![ProcedureError::TooFewCrates {
+                line: 7,
+                command: String::from("move 9 from 1 to 3"),
+                present: 5,
+                state: String::from(state),
+            }])
+        };
+        assert_eq!(simulate(&bad, &CrateMover9000), too_few("1:ZNDCM 2: 3:P"));
+        assert_eq!(simulate(&bad, &CrateMover9001), too_few("1:ZNMCD 2: 3:P"));
+
+        // Moves after a blank line are still checked, and both ends of a move
+        let bad =
+            format!("{drawing}\n\nmove 1 from 2 to 1\n\nmove 9 from 7 to 1\nmove 1 from 0 to 4\n");
+        let state = String::from("1:ZND 2:MC 3:P");
+        assert_eq!(
+            simulate(&bad, &CrateMover9000),
+            Err(vec![
+                ProcedureError::NoSuchStack {
+                    line: 8,
+                    command: String::from("move 9 from 7 to 1"),
+                    stack: 7,
+                    state: state.clone(),
+                },
+                ProcedureError::NoSuchStack {
+                    line: 9,
+                    command: String::from("move 1 from 0 to 4"),
+                    stack: 0,
+                    state: state.clone(),
+                },
+                ProcedureError::NoSuchStack {
+                    line: 9,
+                    command: String::from("move 1 from 0 to 4"),
+                    stack: 4,
+                    state,
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn parses_any_drawing() {
+        // Trailing spaces stripped, twelve stacks and crates with longer names
+        let contents = [
+            format!("    [AB]{}[Q]", " ".repeat(36)),
+            format!("[C] [D]{}[X] [Y]", " ".repeat(33)),
+            String::from(" 1   2   3   4   5   6   7   8   9   10  11  12"),
+            String::new(),
+            String::from("move 2 from 2 to 10"),
+            String::from("move 300 from 3 to 4"),
+        ]
+        .join("\n");
+        let (stacks, commands, errors) = parse(&contents).unwrap();
+        assert_eq!(errors, vec![]);
+        assert_eq!(stacks.len(), 12);
+        assert_eq!(stacks[1], vec!["D", "AB"]);
+        assert_eq!(stacks[11], vec!["Y", "Q"]);
+        assert_eq!(stacks.tops(), "CABXQ");
+        assert_eq!(commands[1].count, 300);
+
+        let rendered = format!("{stacks}\n\n");
+        assert_eq!(Input::new(&rendered).stacks().unwrap(), stacks);
+
+        let mut stacks = stacks;
+        CrateMover9001.apply(&mut stacks, &commands[0]);
+        assert_eq!(stacks.summary().split(' ').nth(9), Some("10:D,AB"));
+
+        assert_eq!(
+            parse("    [A]\n[B]\n 1   2\n\n").err(),
+            Some(ProcedureError::BadDrawing {
+                line: 1,
+                reason: String::from("crate [A] has nothing under it")
+            })
+        );
+        assert_eq!(
+            parse("[A] x\n 1   2\n\n").err(),
+            Some(ProcedureError::BadDrawing {
+                line: 1,
+                reason: String::from("'x' at column 5 is not part of a crate")
+            })
+        );
+        assert_eq!(
+            parse("[A]\n 2\n\n").err(),
+            Some(ProcedureError::BadDrawing {
+                line: 2,
+                reason: String::from("stacks should be numbered from 1 in order")
+            })
+        );
+    }
 }
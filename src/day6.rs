@@ -1,6 +1,9 @@
-use std::fs;
-// use std::str;
 use std::collections::HashSet;
+use std::fs;
+
+use crate::bench;
+use crate::bitset::BitSet128;
+use crate::ring_buffer::RingBuffer;
 
 const DAY: i32 = 6;
 
@@ -8,54 +11,50 @@ fn file_path() -> String {
     format!("src/input{DAY}.txt")
 }
 
-#[derive(Debug)]
-struct Buffer<T> {
-    memory: Vec<T>,
-    capacity: usize,
-    position: usize,
-    end: usize,
-}
-impl<T: Clone> Buffer<T> {
-    pub fn with_capacity(capacity: usize, default: T) -> Buffer<T> {
-        let mut v = Vec::with_capacity(capacity);
-        v.extend(std::iter::repeat(default).take(capacity));
-        Buffer {
-            memory: v,
-            capacity,
-            position: 0,
-            end: 0,
-        }
+/// 1 based position of the end of the first window of `size` distinct characters
+fn first_marker(contents: &str, size: usize) -> Option<usize> {
+    // Only ASCII fits in the bit set
+    if !contents.is_ascii() {
+        return first_marker_hashset(contents, size);
     }
-    pub fn push(&mut self, val: T) {
-        self.memory[self.position] = val;
-        self.position += 1;
-        if self.position >= self.capacity {
-            self.position = 0;
+    let mut buf = RingBuffer::with_capacity(size);
+    for (i, c) in contents.chars().enumerate() {
+        buf.push(c);
+        // Not enough data
+        if !buf.is_full() {
+            continue;
+        }
+        let set: BitSet128 = buf.iter().map(|&c| c as u32).collect();
+        if set.len() as usize == buf.capacity() {
+            // Need 1 based indexing
+            return Some(i + 1);
         }
     }
+    None
 }
 
-pub fn part_a() {
-    let contents =
-        fs::read_to_string(file_path()).expect("Should have been able to read {DAY} the file");
-
-    let mut results: usize = 0;
-    let mut buf = Buffer::with_capacity(4, ' ');
+fn first_marker_hashset(contents: &str, size: usize) -> Option<usize> {
+    let mut buf = RingBuffer::with_capacity(size);
     for (i, c) in contents.chars().enumerate() {
         buf.push(c);
         // Not enough data
-        if i < 4 {
+        if !buf.is_full() {
             continue;
         }
-        let set: HashSet<char> = HashSet::from_iter(buf.memory.clone());
-        let m = &buf.memory;
-        println!("{m:?}");
-        if set.len() == 4 {
+        let set: HashSet<&char> = HashSet::from_iter(&buf);
+        if set.len() == buf.capacity() {
             // Need 1 based indexing
-            results = i + 1;
-            break;
+            return Some(i + 1);
         }
     }
+    None
+}
+
+pub fn part_a() {
+    let contents =
+        fs::read_to_string(file_path()).expect("Should have been able to read {DAY} the file");
+
+    let results = first_marker(&contents, 4).expect("Should have found a marker");
 
     // Solution 1343
     println!("Day {DAY}a best:\n{results}");
@@ -65,28 +64,27 @@ pub fn part_b() {
     let contents =
         fs::read_to_string(file_path()).expect("Should have been able to read {DAY} the file");
 
-    let mut results: usize = 0;
-    let mut buf = Buffer::with_capacity(14, ' ');
-    for (i, c) in contents.chars().enumerate() {
-        buf.push(c);
-        // Not enough data
-        if i < 4 {
-            continue;
-        }
-        let set: HashSet<char> = HashSet::from_iter(buf.memory.clone());
-        let m = &buf.memory;
-        println!("{m:?}");
-        if set.len() == buf.capacity {
-            // Need 1 based indexing
-            results = i + 1;
-            break;
-        }
-    }
+    let results = first_marker(&contents, 14).expect("Should have found a marker");
 
     // Solution 2193
     println!("Day {DAY}b best:\n{results}");
 }
 
+/// Compare the hash set and bit set implementations
+pub fn part_bench() {
+    let contents =
+        fs::read_to_string(file_path()).expect("Should have been able to read {DAY} the file");
+
+    for size in [4, 14] {
+        bench::compare(
+            &format!("Day 6 window {size} HashSet -> BitSet128"),
+            200,
+            || first_marker_hashset(&contents, size),
+            || first_marker(&contents, size),
+        );
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -100,4 +98,18 @@ mod tests {
     fn do_part_b() {
         part_b();
     }
+
+    #[test]
+    fn bitset_matches_hashset() {
+        let contents = "mjqjpqmgbljsphdztnvjfqwrcgsmlb";
+        assert_eq!(first_marker(contents, 4), Some(7));
+        assert_eq!(first_marker_hashset(contents, 4), Some(7));
+        assert_eq!(first_marker(contents, 14), Some(19));
+        assert_eq!(first_marker_hashset(contents, 14), Some(19));
+    }
+
+    #[test]
+    fn non_ascii_falls_back() {
+        assert_eq!(first_marker("ééßéñø", 4), Some(6));
+    }
 }
@@ -1,8 +1,9 @@
 use std::cmp;
-use std::collections::BinaryHeap;
 use std::fs;
 use std::str;
 
+use crate::top_k::TopK;
+
 const DAY: i32 = 3;
 
 struct Input<'a> {
@@ -54,22 +55,18 @@ pub fn part_b() {
 
     let contents = fs::read_to_string(file_path).expect("Should have been able to read the file");
 
-    let count = 3;
     let mut current = 0;
-    let mut heap = BinaryHeap::new();
+    let mut top = TopK::new(3);
     for line in contents.lines() {
         if line.is_empty() {
-            heap.push(current * -1);
-            if heap.len() > count {
-                heap.pop();
-            }
+            top.push(current);
             current = 0;
         } else {
             current += line.parse::<i32>().unwrap();
         }
     }
-    let results: i32 = heap.iter().sum();
-    let actual_results = results * -1;
+    top.push(current);
+    let actual_results: i32 = top.into_sorted_vec().iter().sum();
     // Solution
     println!("Day {DAY}b best:\n{actual_results}");
 }
@@ -0,0 +1,228 @@
+use std::cmp;
+use std::fmt;
+
+/// Inclusive range of integers, `start..=end`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Interval {
+    pub start: u32,
+    pub end: u32,
+}
+
+impl Interval {
+    pub fn new(start: u32, end: u32) -> Interval {
+        assert!(start <= end, "interval start {start} is after end {end}");
+        Interval { start, end }
+    }
+
+    /// Number of integers covered
+    pub fn len(&self) -> u64 {
+        (self.end - self.start) as u64 + 1
+    }
+
+    pub fn contains(&self, other: &Interval) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    pub fn contains_point(&self, point: u32) -> bool {
+        self.start <= point && point <= self.end
+    }
+
+    pub fn overlaps(&self, other: &Interval) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    pub fn intersection(&self, other: &Interval) -> Option<Interval> {
+        let start = cmp::max(self.start, other.start);
+        let end = cmp::min(self.end, other.end);
+        if start <= end {
+            Some(Interval { start, end })
+        } else {
+            None
+        }
+    }
+
+    /// Smallest interval covering both
+    pub fn hull(&self, other: &Interval) -> Interval {
+        Interval {
+            start: cmp::min(self.start, other.start),
+            end: cmp::max(self.end, other.end),
+        }
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+/// Set of integers stored as sorted, disjoint, non-adjacent intervals
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntervalSet {
+    intervals: Vec<Interval>,
+}
+
+impl IntervalSet {
+    pub fn new() -> IntervalSet {
+        IntervalSet::default()
+    }
+
+    pub fn intervals(&self) -> &[Interval] {
+        &self.intervals
+    }
+
+    pub fn insert(&mut self, interval: Interval) {
+        // First interval that could merge with the new one
+        let lo = self
+            .intervals
+            .partition_point(|i| i.end.saturating_add(1) < interval.start);
+        // One past the last interval that could merge with the new one
+        let hi = self
+            .intervals
+            .partition_point(|i| i.start <= interval.end.saturating_add(1));
+
+        let merged = self.intervals[lo..hi]
+            .iter()
+            .fold(interval, |acc, i| acc.hull(i));
+        self.intervals.splice(lo..hi, [merged]);
+    }
+
+    // Not needed by day4, kept so the set can answer membership
+    #[allow(dead_code)]
+    pub fn contains_point(&self, point: u32) -> bool {
+        let idx = self.intervals.partition_point(|i| i.end < point);
+        self.intervals
+            .get(idx)
+            .is_some_and(|i| i.contains_point(point))
+    }
+
+    // Day4 builds its sets with `extend` instead
+    #[allow(dead_code)]
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        let mut results = self.clone();
+        results.extend(other.intervals.iter().copied());
+        results
+    }
+
+    /// Everything in `self` that is not in `other`
+    pub fn difference(&self, other: &IntervalSet) -> IntervalSet {
+        let mut results = IntervalSet::new();
+        for interval in &self.intervals {
+            // First value not yet known to be cut away
+            let mut next = Some(interval.start);
+            for cut in other.intervals.iter().filter(|o| o.overlaps(interval)) {
+                let start = next.unwrap();
+                if cut.start > start {
+                    results.intervals.push(Interval::new(start, cut.start - 1));
+                }
+                if cut.end >= interval.end {
+                    next = None;
+                    break;
+                }
+                next = Some(cmp::max(start, cut.end + 1));
+            }
+            if let Some(start) = next {
+                results.intervals.push(Interval::new(start, interval.end));
+            }
+        }
+        results
+    }
+
+    /// Number of integers covered
+    pub fn coverage_len(&self) -> u64 {
+        self.intervals.iter().map(Interval::len).sum()
+    }
+
+    /// Smallest interval covering the whole set
+    pub fn hull(&self) -> Option<Interval> {
+        match (self.intervals.first(), self.intervals.last()) {
+            (Some(first), Some(last)) => Some(first.hull(last)),
+            _ => None,
+        }
+    }
+
+    /// Uncovered ranges between the first and last covered values
+    // Day4 wants the gaps before the first range too, so uses `gaps_within`
+    #[allow(dead_code)]
+    pub fn gaps(&self) -> Vec<Interval> {
+        self.intervals
+            .windows(2)
+            .map(|w| Interval::new(w[0].end + 1, w[1].start - 1))
+            .collect()
+    }
+
+    /// Uncovered ranges within `bounds`
+    pub fn gaps_within(&self, bounds: Interval) -> Vec<Interval> {
+        let mut everything = IntervalSet::new();
+        everything.insert(bounds);
+        everything.difference(self).intervals
+    }
+}
+
+impl Extend<Interval> for IntervalSet {
+    fn extend<I: IntoIterator<Item = Interval>>(&mut self, iter: I) {
+        for interval in iter {
+            self.insert(interval);
+        }
+    }
+}
+
+impl FromIterator<Interval> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = Interval>>(iter: I) -> Self {
+        let mut results = IntervalSet::new();
+        results.extend(iter);
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(ranges: &[(u32, u32)]) -> IntervalSet {
+        ranges.iter().map(|&(s, e)| Interval::new(s, e)).collect()
+    }
+
+    #[test]
+    fn interval_relations() {
+        let a = Interval::new(2, 8);
+        let b = Interval::new(3, 7);
+        let c = Interval::new(9, 12);
+        assert!(a.contains(&b));
+        assert!(!b.contains(&a));
+        assert!(a.overlaps(&b));
+        assert!(!a.overlaps(&c));
+        assert_eq!(a.intersection(&b), Some(b));
+        assert_eq!(a.intersection(&c), None);
+        assert_eq!(a.hull(&c), Interval::new(2, 12));
+        assert_eq!(Interval::new(6, 6).len(), 1);
+    }
+
+    #[test]
+    fn set_insert_merges() {
+        let s = set(&[(5, 7), (1, 2), (3, 4), (10, 12), (11, 20)]);
+        assert_eq!(s, set(&[(1, 7), (10, 20)]));
+        assert_eq!(s.coverage_len(), 18);
+        assert_eq!(s.gaps(), vec![Interval::new(8, 9)]);
+        assert!(s.contains_point(7));
+        assert!(!s.contains_point(8));
+    }
+
+    #[test]
+    fn set_union_and_difference() {
+        let a = set(&[(1, 10), (20, 30)]);
+        let b = set(&[(5, 6), (9, 22), (30, 40)]);
+        assert_eq!(a.union(&b), set(&[(1, 40)]));
+        assert_eq!(a.difference(&b), set(&[(1, 4), (7, 8), (23, 29)]));
+        assert_eq!(b.difference(&a), set(&[(11, 19), (31, 40)]));
+        assert_eq!(a.difference(&a), IntervalSet::new());
+        assert_eq!(
+            a.gaps_within(Interval::new(0, 35)),
+            vec![
+                Interval::new(0, 0),
+                Interval::new(11, 19),
+                Interval::new(31, 35)
+            ]
+        );
+    }
+}
@@ -1,3 +1,5 @@
+mod bench;
+mod bitset;
 mod day1;
 mod day10;
 mod day11;
@@ -11,6 +13,10 @@ mod day6;
 mod day7;
 mod day8;
 mod day9;
+mod interval;
+mod ocr;
+mod ring_buffer;
+mod top_k;
 
 use std::collections::HashMap;
 use std::env;
@@ -28,12 +34,15 @@ fn main() {
         ("2b", day2::part_b as VoidFunc),
         ("3a", day3::part_a as VoidFunc),
         ("3b", day3::part_b as VoidFunc),
+        ("3bench", day3::part_bench as VoidFunc),
         ("4a", day4::part_a as VoidFunc),
         ("4b", day4::part_b as VoidFunc),
+        ("4gaps", day4::part_gaps as VoidFunc),
         ("5a", day5::part_a as VoidFunc),
         ("5b", day5::part_b as VoidFunc),
         ("6a", day6::part_a as VoidFunc),
         ("6b", day6::part_b as VoidFunc),
+        ("6bench", day6::part_bench as VoidFunc),
         ("7a", day7::part_a as VoidFunc),
         ("7b", day7::part_b as VoidFunc),
         ("8a", day8::part_a as VoidFunc),
@@ -49,6 +58,34 @@ fn main() {
         ("13a", day13::part_a as VoidFunc),
         ("13b", day13::part_b as VoidFunc),
     ]);
+
+    // Commands that take extra arguments after the command name
+    type ArgsFunc = fn(&[String]);
+    let tools: HashMap<&str, ArgsFunc> = HashMap::from([
+        ("1top", day1::part_top as ArgsFunc),
+        ("1stats", day1::part_stats as ArgsFunc),
+        ("1rebalance", day1::part_rebalance as ArgsFunc),
+        ("2game", day2::part_game as ArgsFunc),
+        ("2explore", day2::part_explore as ArgsFunc),
+        ("2model", day2::part_model as ArgsFunc),
+        ("2tournament", day2::part_tournament as ArgsFunc),
+        ("3groups", day3::part_groups as ArgsFunc),
+        ("3repack", day3::part_repack as ArgsFunc),
+        ("3scheme", day3::part_scheme as ArgsFunc),
+        ("4graph", day4::part_graph as ArgsFunc),
+        ("4reassign", day4::part_reassign as ArgsFunc),
+        ("4sweep", day4::part_sweep as ArgsFunc),
+        ("5animate", day5::part_animate as ArgsFunc),
+        ("5crane", day5::part_crane as ArgsFunc),
+        ("5show", day5::part_show as ArgsFunc),
+        ("5validate", day5::part_validate as ArgsFunc),
+        ("11top", day11::part_top as ArgsFunc),
+    ]);
+    if let Some(func) = tools.get(cmd) {
+        func(args.get(2..).unwrap_or_default());
+        return;
+    }
+
     let func = days
         .get(cmd)
         .expect("Should have had a function for day {cmd}");
@@ -0,0 +1,129 @@
+use std::fmt;
+
+pub const GLYPH_HEIGHT: usize = 6;
+pub const GLYPH_WIDTH: usize = 4;
+// Glyphs are separated by one blank column
+const GLYPH_PITCH: usize = GLYPH_WIDTH + 1;
+
+/// The 4x6 font used by the elves' displays, one row per string
+const FONT: [(char, [&str; GLYPH_HEIGHT]); 18] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...", "#...", ".#.#", "..#.", "..#.", "..#."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum OcrError {
+    /// The image does not have exactly `GLYPH_HEIGHT` rows
+    BadHeight(usize),
+    /// Rows are not all the same width
+    RaggedRows,
+    /// The columns don't split into whole glyphs
+    BadWidth(usize),
+    /// Glyph number `index` (0 based) is not in the font
+    UnknownGlyph { index: usize, glyph: Vec<String> },
+}
+
+impl fmt::Display for OcrError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OcrError::BadHeight(h) => {
+                write!(f, "expected {GLYPH_HEIGHT} rows of pixels, found {h}")
+            }
+            OcrError::RaggedRows => write!(f, "rows of pixels have different widths"),
+            OcrError::BadWidth(w) => write!(
+                f,
+                "{w} columns of pixels are not whole {GLYPH_WIDTH} column glyphs"
+            ),
+            OcrError::UnknownGlyph { index, glyph } => {
+                writeln!(f, "unknown glyph at position {index}:")?;
+                for row in glyph {
+                    writeln!(f, "{row}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Read the letters out of an image made of `#` and `.` rows
+pub fn read<S: AsRef<str>>(rows: &[S]) -> Result<String, OcrError> {
+    if rows.len() != GLYPH_HEIGHT {
+        return Err(OcrError::BadHeight(rows.len()));
+    }
+    let rows: Vec<Vec<char>> = rows.iter().map(|r| r.as_ref().chars().collect()).collect();
+    let width = rows[0].len();
+    if rows.iter().any(|r| r.len() != width) {
+        return Err(OcrError::RaggedRows);
+    }
+
+    // The trailing separator column may be missing
+    let whole = width.is_multiple_of(GLYPH_PITCH) || (width + 1).is_multiple_of(GLYPH_PITCH);
+    if width < GLYPH_WIDTH || !whole {
+        return Err(OcrError::BadWidth(width));
+    }
+    let count = (width + 1) / GLYPH_PITCH;
+    (0..count)
+        .map(|index| {
+            let offset = index * GLYPH_PITCH;
+            let glyph: Vec<String> = rows
+                .iter()
+                .map(|r| r[offset..offset + GLYPH_WIDTH].iter().collect())
+                .collect();
+            FONT.iter()
+                .find(|(_, pattern)| pattern.iter().zip(&glyph).all(|(p, g)| p == g))
+                .map(|(letter, _)| *letter)
+                .ok_or(OcrError::UnknownGlyph { index, glyph })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_letters() {
+        let rows = [
+            "#..#.####.#....#.....##..",
+            "#..#.#....#....#....#..#.",
+            "####.###..#....#....#..#.",
+            "#..#.#....#....#....#..#.",
+            "#..#.#....#....#....#..#.",
+            "#..#.####.####.####..##..",
+        ];
+        assert_eq!(read(&rows), Ok(String::from("HELLO")));
+    }
+
+    #[test]
+    fn reports_unknown_glyph() {
+        let rows = ["####", "#..#", "#..#", "#..#", "#..#", "####"];
+        let err = read(&rows).unwrap_err();
+        assert!(matches!(err, OcrError::UnknownGlyph { index: 0, .. }));
+        assert!(err.to_string().contains("#..#\n####"));
+    }
+
+    #[test]
+    fn reports_partial_glyphs() {
+        let rows = ["#..#..", "#..#..", "####..", "#..#..", "#..#..", "#..#.."];
+        assert_eq!(read(&rows), Err(OcrError::BadWidth(6)));
+        let rows = ["#.", "#.", "#.", "#.", "#.", "#."];
+        assert_eq!(read(&rows), Err(OcrError::BadWidth(2)));
+        assert_eq!(read(&[""; GLYPH_HEIGHT]), Err(OcrError::BadWidth(0)));
+    }
+}
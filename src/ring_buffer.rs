@@ -0,0 +1,208 @@
+use std::ops::Index;
+
+/// Fixed-capacity ring buffer. Once full, each push evicts the oldest value.
+#[derive(Debug, Clone)]
+pub struct RingBuffer<T> {
+    memory: Vec<Option<T>>,
+    // Index of the oldest value
+    start: usize,
+    len: usize,
+}
+
+impl<T> RingBuffer<T> {
+    pub fn with_capacity(capacity: usize) -> RingBuffer<T> {
+        assert!(capacity > 0, "ring buffer capacity must be non-zero");
+        let mut memory = Vec::with_capacity(capacity);
+        memory.resize_with(capacity, || None);
+        RingBuffer {
+            memory,
+            start: 0,
+            len: 0,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.memory.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len == self.capacity()
+    }
+
+    /// Physical slot of the `i`th oldest value
+    fn slot(&self, i: usize) -> usize {
+        (self.start + i) % self.capacity()
+    }
+
+    /// Push a value on the back, returning the evicted oldest value when full.
+    pub fn push(&mut self, val: T) -> Option<T> {
+        if self.is_full() {
+            let evicted = self.memory[self.start].replace(val);
+            self.start = self.slot(1);
+            evicted
+        } else {
+            let end = self.slot(self.len);
+            self.memory[end] = Some(val);
+            self.len += 1;
+            None
+        }
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        let val = self.memory[self.start].take();
+        self.start = self.slot(1);
+        self.len -= 1;
+        val
+    }
+
+    /// Get the `i`th value, counting from the oldest.
+    pub fn get(&self, i: usize) -> Option<&T> {
+        if i >= self.len {
+            return None;
+        }
+        self.memory[self.slot(i)].as_ref()
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        self.len.checked_sub(1).and_then(|i| self.get(i))
+    }
+
+    pub fn clear(&mut self) {
+        self.memory.iter_mut().for_each(|m| *m = None);
+        self.start = 0;
+        self.len = 0;
+    }
+
+    /// Iterate from oldest to newest
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            buffer: self,
+            front: 0,
+            back: self.len,
+        }
+    }
+}
+
+impl<T> Index<usize> for RingBuffer<T> {
+    type Output = T;
+
+    fn index(&self, i: usize) -> &Self::Output {
+        let len = self.len;
+        self.get(i)
+            .unwrap_or_else(|| panic!("index {i} out of range for ring buffer of length {len}"))
+    }
+}
+
+impl<T> Extend<T> for RingBuffer<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for val in iter {
+            self.push(val);
+        }
+    }
+}
+
+pub struct Iter<'a, T> {
+    buffer: &'a RingBuffer<T>,
+    front: usize,
+    back: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        let val = self.buffer.get(self.front);
+        self.front += 1;
+        val
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.back - self.front;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        self.back -= 1;
+        self.buffer.get(self.back)
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+impl<'a, T> IntoIterator for &'a RingBuffer<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_wraps_and_evicts() {
+        let mut buf = RingBuffer::with_capacity(3);
+        assert!(buf.is_empty());
+        assert_eq!(buf.push(1), None);
+        assert_eq!(buf.push(2), None);
+        assert!(!buf.is_full());
+        assert_eq!(buf.push(3), None);
+        assert!(buf.is_full());
+        assert_eq!(buf.push(4), Some(1));
+        assert_eq!(buf.len(), 3);
+        assert_eq!(buf.iter().copied().collect::<Vec<_>>(), vec![2, 3, 4]);
+        assert_eq!(buf.iter().rev().copied().collect::<Vec<_>>(), vec![4, 3, 2]);
+        assert_eq!((buf[0], buf[2]), (2, 4));
+        assert_eq!(buf.get(3), None);
+        assert_eq!((buf.front(), buf.back()), (Some(&2), Some(&4)));
+    }
+
+    #[test]
+    fn pop_front_and_extend() {
+        let mut buf = RingBuffer::with_capacity(4);
+        buf.extend("abcdef".chars());
+        assert_eq!(buf.iter().collect::<String>(), "cdef");
+        assert_eq!(buf.pop_front(), Some('c'));
+        assert_eq!(buf.pop_front(), Some('d'));
+        assert_eq!(buf.len(), 2);
+        buf.push('g');
+        assert_eq!(buf.iter().collect::<String>(), "efg");
+        buf.clear();
+        assert_eq!(buf.pop_front(), None);
+        assert_eq!(buf.back(), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_out_of_range() {
+        let mut buf = RingBuffer::with_capacity(2);
+        buf.push(1);
+        let _ = buf[1];
+    }
+}
@@ -0,0 +1,144 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+
+struct Entry<K, T> {
+    key: K,
+    // Insertion order, so earlier values win ties
+    seq: usize,
+    val: T,
+}
+
+impl<K: Ord, T> Entry<K, T> {
+    fn rank(&self) -> (&K, Reverse<usize>) {
+        (&self.key, Reverse(self.seq))
+    }
+}
+
+impl<K: Ord, T> PartialEq for Entry<K, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.rank() == other.rank()
+    }
+}
+
+impl<K: Ord, T> Eq for Entry<K, T> {}
+
+impl<K: Ord, T> PartialOrd for Entry<K, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K: Ord, T> Ord for Entry<K, T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.rank().cmp(&other.rank())
+    }
+}
+
+/// Keeps the `k` largest values seen, using O(k) memory.
+///
+/// Values with equal keys are ranked in the order they were pushed.
+pub struct TopK<T, K, F> {
+    k: usize,
+    key: F,
+    seq: usize,
+    // Min heap, so the weakest kept value is on top ready to be evicted
+    heap: BinaryHeap<Reverse<Entry<K, T>>>,
+}
+
+impl<T: Ord + Clone> TopK<T, T, fn(&T) -> T> {
+    pub fn new(k: usize) -> Self {
+        TopK::by_key(k, T::clone)
+    }
+}
+
+impl<T, K: Ord, F: Fn(&T) -> K> TopK<T, K, F> {
+    pub fn by_key(k: usize, key: F) -> Self {
+        TopK {
+            k,
+            key,
+            seq: 0,
+            heap: BinaryHeap::with_capacity(k + 1),
+        }
+    }
+
+    pub fn push(&mut self, val: T) {
+        let entry = Entry {
+            key: (self.key)(&val),
+            seq: self.seq,
+            val,
+        };
+        self.seq += 1;
+
+        if self.heap.len() < self.k {
+            self.heap.push(Reverse(entry));
+        } else if let Some(mut weakest) = self.heap.peek_mut() {
+            if entry > weakest.0 {
+                *weakest = Reverse(entry);
+            }
+        }
+    }
+
+    // Days 1 and 11 only need the sorted results
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Kept values, largest first
+    pub fn into_sorted_vec(self) -> Vec<T> {
+        // Ascending order of Reverse is descending order of the entries
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse(e)| e.val)
+            .collect()
+    }
+}
+
+impl<T, K: Ord, F: Fn(&T) -> K> Extend<T> for TopK<T, K, F> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for val in iter {
+            self.push(val);
+        }
+    }
+}
+
+/// The `k` largest values, largest first
+pub fn top_k<T: Ord + Clone>(iter: impl IntoIterator<Item = T>, k: usize) -> Vec<T> {
+    let mut top = TopK::new(k);
+    top.extend(iter);
+    top.into_sorted_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_largest_sorted() {
+        assert_eq!(top_k([5, 1, 9, 3, 7, 9, 2], 3), vec![9, 9, 7]);
+        assert_eq!(top_k([5, 1], 3), vec![5, 1]);
+        assert_eq!(top_k([5, 1], 0), Vec::<i32>::new());
+    }
+
+    #[test]
+    fn ties_are_stable_by_key() {
+        let mut top = TopK::by_key(3, |(_, calories): &(char, u32)| *calories);
+        assert!(top.is_empty());
+        top.extend([
+            ('a', 10),
+            ('b', 30),
+            ('c', 20),
+            ('d', 30),
+            ('e', 20),
+            ('f', 5),
+        ]);
+        assert_eq!(top.len(), 3);
+        assert_eq!(top.into_sorted_vec(), vec![('b', 30), ('d', 30), ('c', 20)]);
+    }
+}